@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::config::{Config, ConfigInput, Configs, DataType};
+use crate::reference::binance::binance_symbol;
+use crate::rules;
+use crate::snapshot::SnapshotFormat;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Monitoring of the Pragma oracle, exposed as Prometheus metrics"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Default, Subcommand)]
+pub enum Command {
+    /// Monitor the configured networks and serve the metrics (default)
+    #[default]
+    Serve,
+    /// Validate the config against the oracle and print the resolved pairs and publishers
+    CheckConfig {
+        /// Only fail on fatal issues, not on warnings (e.g unknown pairs)
+        #[arg(long)]
+        allow_warnings: bool,
+    },
+    /// Run every check once, print the computed values and exit
+    Snapshot {
+        #[arg(long, value_enum, default_value_t)]
+        format: SnapshotFormat,
+    },
+    /// Generate the Prometheus alerting rules of the configured pairs and thresholds
+    GenerateRules {
+        /// File to write the rules to, printed when not set
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Validates the config of the networks to monitor and prints it along with the
+/// validation report, without starting the monitoring.
+/// Returns the exit code of the process, non-zero on any issue (or only on fatal ones
+/// when warnings are allowed).
+pub async fn check_config(allow_warnings: bool) -> i32 {
+    let configs = match Configs::create_from_env().await {
+        Ok(configs) => configs,
+        Err(report) => {
+            println!("{report}");
+            return 1;
+        }
+    };
+
+    for config in configs.networks() {
+        print_config(config);
+    }
+    println!("{}", configs.report());
+
+    match !allow_warnings && !configs.report().is_empty() {
+        true => 1,
+        false => 0,
+    }
+}
+
+/// Generates the alerting rules from the config, without reading the oracle.
+/// Returns the exit code of the process.
+pub fn generate_rules(output: Option<PathBuf>) -> i32 {
+    let config_inputs = match ConfigInput::all_from_env() {
+        Ok((config_inputs, _, report)) => {
+            if !report.is_empty() {
+                eprintln!("{report}");
+            }
+            config_inputs
+        }
+        Err(report) => {
+            eprintln!("{report}");
+            return 1;
+        }
+    };
+
+    let rules = rules::generate_rules(&config_inputs);
+    match output {
+        Some(path) => match std::fs::write(&path, rules) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("Failed to write the rules to {}: {e}", path.display());
+                1
+            }
+        },
+        None => {
+            print!("{rules}");
+            0
+        }
+    }
+}
+
+fn print_config(config: &Config) {
+    let network = config.network();
+    println!(
+        "Network {} (chain id {:#x}, oracle {:#x})",
+        network.name, network.chain_id, network.oracle_address
+    );
+
+    for data_type in [DataType::Spot, DataType::Future] {
+        println!(
+            "  {data_type} pairs (table {}):",
+            config.table_name(data_type.clone())
+        );
+
+        let mut pairs = config.sources(data_type.clone()).iter().collect::<Vec<_>>();
+        pairs.sort();
+        for (pair, sources) in pairs {
+            let decimals = config.decimals(data_type.clone())[pair];
+            // Futures are priced from their Binance perpetual contract
+            let reference_id = match data_type {
+                DataType::Spot => config.reference_id(pair).map(str::to_string),
+                DataType::Future => binance_symbol(pair).ok(),
+            }
+            .unwrap_or("none".to_string());
+            println!(
+                "    {pair}: {decimals} decimals, reference id {reference_id}, sources {}",
+                sources.join(", ")
+            );
+        }
+    }
+
+    println!(
+        "  Publishers (registry {:#x}):",
+        network.publisher_registry_address
+    );
+    let mut publishers = config.all_publishers().iter().collect::<Vec<_>>();
+    publishers.sort();
+    for (publisher, address) in publishers {
+        println!("    {publisher}: {address:#x}");
+    }
+}
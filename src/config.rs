@@ -1,13 +1,16 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt,
     str::FromStr,
     sync::Arc,
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 use arc_swap::{ArcSwap, Guard};
+use serde::Deserialize;
 use starknet::{
     core::{
+        chain_id,
         types::{BlockId, BlockTag, Felt, FunctionCall},
         utils::{cairo_short_string_to_felt, parse_cairo_short_string},
     },
@@ -15,17 +18,54 @@ use starknet::{
     providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider},
 };
 use strum::{Display, EnumString, IntoStaticStr};
-use tokio::sync::OnceCell;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::OnceCell,
+};
 use url::Url;
 
-use crate::{constants::CONFIG_UPDATE_INTERVAL, utils::try_felt_to_u32};
+use crate::{
+    constants::{
+        COINGECKO_IDS, CONFIG_FILE_CHECK_INTERVAL, CONFIG_GENERATION, CONFIG_LAST_RELOAD_TIMESTAMP,
+        CONFIG_UPDATE_INTERVAL, DEFAULT_NUM_SOURCES_THRESHOLD, DEFAULT_ON_OFF_DEVIATION_THRESHOLD,
+        DEFAULT_PRICE_DEVIATION_THRESHOLD, DEFAULT_REFERENCE_CACHE_BUCKET,
+        DEFAULT_REFERENCE_CACHE_TTL, DEFAULT_REFERENCE_MAX_DEVIATION,
+        DEFAULT_REFERENCE_MIN_AGREEING, DEFAULT_REFERENCE_PROVIDERS,
+        DEFAULT_SOURCE_DEVIATION_THRESHOLD, DEFAULT_TIME_SINCE_LAST_UPDATE_THRESHOLD,
+        MISSING_REFERENCE_ID, REFERENCE_PROVIDERS,
+    },
+    registry::{report_changes, RegistryState},
+    utils::try_felt_to_u32,
+    validation::ValidationReport,
+};
 
-#[derive(Debug, Clone, EnumString, IntoStaticStr)]
-pub enum NetworkName {
-    #[strum(ascii_case_insensitive)]
-    Mainnet,
-    #[strum(ascii_case_insensitive)]
-    Testnet,
+/// Name of a monitored network, used as the `network` label of the metrics.
+/// `mainnet` and `testnet` are normalized to `Mainnet` and `Testnet`, any other
+/// network (devnet, appchain...) keeps its configured name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkName(String);
+
+impl NetworkName {
+    pub const MAINNET: &'static str = "Mainnet";
+    pub const TESTNET: &'static str = "Testnet";
+
+    pub fn new(name: &str) -> Self {
+        let name = [Self::MAINNET, Self::TESTNET]
+            .into_iter()
+            .find(|known| known.eq_ignore_ascii_case(name))
+            .unwrap_or(name);
+        Self(name.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for NetworkName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[derive(Debug, EnumString, IntoStaticStr, PartialEq, Eq, Hash, Clone, Display)]
@@ -36,13 +76,288 @@ pub enum DataType {
     Future,
 }
 
+/// Checks that can be enabled or disabled per pair.
+#[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// Deviation of the sources prices from the reference price.
+    PriceDeviation,
+    /// Deviation of the sources prices from the on-chain median.
+    SourceDeviation,
+    /// Deviation of the on-chain median from the reference price.
+    OnOffDeviation,
+    /// Deviation of the Pragma API price from the reference price.
+    Api,
+    /// Freshness and deviation of the spot checkpoints.
+    Checkpoint,
+}
+
+/// What the data checks do when the indexer of their table is not synced,
+/// or when its status cannot be checked.
+/// Given as `skip`, `run` or `max_lag:<blocks>`.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(try_from = "String")]
+pub enum SyncingPolicy {
+    /// Skip the tick.
+    #[default]
+    Skip,
+    /// Run the tick anyway, flagging it with the `monitor_running_unsynced` metric.
+    Run,
+    /// Run the tick (flagged) if the indexer is at most the given number of blocks behind.
+    MaxLag(u64),
+}
+
+impl FromStr for SyncingPolicy {
+    type Err = String;
+
+    fn from_str(policy: &str) -> Result<Self, Self::Err> {
+        match policy.trim().to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "run" => Ok(Self::Run),
+            other => other
+                .strip_prefix("max_lag:")
+                .and_then(|blocks| blocks.trim().parse().ok())
+                .map(Self::MaxLag)
+                .ok_or(format!(
+                    "invalid syncing policy {policy}, expected skip, run or max_lag:<blocks>"
+                )),
+        }
+    }
+}
+
+impl TryFrom<String> for SyncingPolicy {
+    type Error = String;
+
+    fn try_from(policy: String) -> Result<Self, Self::Error> {
+        policy.parse()
+    }
+}
+
+/// Alerting thresholds of a pair.
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+#[allow(unused)]
+pub struct Thresholds {
+    /// Maximum deviation from the reference price. (as a fraction, e.g. 0.05 for 5%)
+    pub price_deviation: Option<f64>,
+    /// Maximum deviation from the on-chain median. (as a fraction, e.g. 0.05 for 5%)
+    pub source_deviation: Option<f64>,
+    /// Maximum deviation of the on-chain median from the reference price. (as a fraction, e.g. 0.05 for 5%)
+    pub on_off_deviation: Option<f64>,
+    /// Maximum time since the last update. (in seconds)
+    pub time_since_last_update: Option<u64>,
+    /// Minimum number of sources aggregated on-chain.
+    pub num_sources: Option<u32>,
+}
+
+impl Thresholds {
+    /// Returns the thresholds, falling back to the given ones when not set.
+    fn or(&self, fallback: &Thresholds) -> Thresholds {
+        Thresholds {
+            price_deviation: self.price_deviation.or(fallback.price_deviation),
+            source_deviation: self.source_deviation.or(fallback.source_deviation),
+            on_off_deviation: self.on_off_deviation.or(fallback.on_off_deviation),
+            time_since_last_update: self
+                .time_since_last_update
+                .or(fallback.time_since_last_update),
+            num_sources: self.num_sources.or(fallback.num_sources),
+        }
+    }
+
+    /// Returns the thresholds with the defaults applied.
+    fn resolve(&self) -> ResolvedThresholds {
+        ResolvedThresholds {
+            price_deviation: self
+                .price_deviation
+                .unwrap_or(DEFAULT_PRICE_DEVIATION_THRESHOLD),
+            source_deviation: self
+                .source_deviation
+                .unwrap_or(DEFAULT_SOURCE_DEVIATION_THRESHOLD),
+            on_off_deviation: self
+                .on_off_deviation
+                .unwrap_or(DEFAULT_ON_OFF_DEVIATION_THRESHOLD),
+            time_since_last_update: self
+                .time_since_last_update
+                .unwrap_or(DEFAULT_TIME_SINCE_LAST_UPDATE_THRESHOLD),
+            num_sources: self.num_sources.unwrap_or(DEFAULT_NUM_SOURCES_THRESHOLD),
+        }
+    }
+}
+
+/// Alerting thresholds of a pair (or of a source of a pair) with the defaults applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedThresholds {
+    pub price_deviation: f64,
+    pub source_deviation: f64,
+    pub on_off_deviation: f64,
+    pub time_since_last_update: u64,
+    pub num_sources: u32,
+}
+
+impl Default for ResolvedThresholds {
+    fn default() -> Self {
+        Thresholds::default().resolve()
+    }
+}
+
+/// Settings of a pair.
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct PairSettings {
+    /// Id of the pair on the reference price providers, only used for spot pairs.
+    pub reference_id: Option<String>,
+    pub thresholds: Thresholds,
+    /// Thresholds of the sources, overriding the ones of the pair.
+    pub sources: HashMap<String, Thresholds>,
+    /// Enabled checks, all of them when not set.
+    pub checks: Option<Vec<Check>>,
+}
+
+impl PairSettings {
+    pub fn is_enabled(&self, check: Check) -> bool {
+        self.checks
+            .as_ref()
+            .is_none_or(|checks| checks.contains(&check))
+    }
+
+    /// Returns the thresholds of the pair, or of one of its sources.
+    pub fn thresholds(&self, source: Option<&str>) -> ResolvedThresholds {
+        match source.and_then(|source| self.sources.get(source)) {
+            Some(thresholds) => thresholds.or(&self.thresholds).resolve(),
+            None => self.thresholds.resolve(),
+        }
+    }
+}
+
+/// Polling intervals of the monitoring tasks. (in seconds)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Intervals {
+    pub monitor: u64,
+    pub api: u64,
+    pub publishers: u64,
+    pub checkpoints: u64,
+    pub vrf: u64,
+    pub indexer: u64,
+    pub config_update: u64,
+}
+
+impl Default for Intervals {
+    fn default() -> Self {
+        Self {
+            monitor: 30,
+            api: 30,
+            publishers: 30,
+            checkpoints: 60,
+            vrf: 60,
+            indexer: 30,
+            config_update: CONFIG_UPDATE_INTERVAL,
+        }
+    }
+}
+
+/// Settings of the reference price providers, shared by all the networks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceSettings {
+    /// Names of the reference providers, in the order they are queried.
+    pub providers: Vec<String>,
+    /// Minimum number of references that must agree in consensus mode.
+    pub min_agreeing: usize,
+    /// Maximum deviation from the median for a reference to agree in consensus mode.
+    pub max_deviation: f64,
+    /// Duration of the timestamp buckets sharing a cached reference price. (in seconds)
+    pub cache_bucket: u64,
+    /// Duration after which a cached reference price is evicted. (in seconds)
+    pub cache_ttl: u64,
+}
+
+impl Default for ReferenceSettings {
+    fn default() -> Self {
+        Self {
+            providers: parse_pairs(DEFAULT_REFERENCE_PROVIDERS),
+            min_agreeing: DEFAULT_REFERENCE_MIN_AGREEING,
+            max_deviation: DEFAULT_REFERENCE_MAX_DEVIATION,
+            cache_bucket: DEFAULT_REFERENCE_CACHE_BUCKET,
+            cache_ttl: DEFAULT_REFERENCE_CACHE_TTL,
+        }
+    }
+}
+
+#[allow(unused)]
+impl ReferenceSettings {
+    /// Reads the settings from the `REFERENCE_*` environment variables.
+    /// Invalid values are reported as fatal issues.
+    pub fn from_env() -> Result<Self, ValidationReport> {
+        let mut report = ValidationReport::default();
+        let defaults = Self::default();
+
+        let settings = Self {
+            providers: env_list("REFERENCE_PROVIDERS")
+                .map(|providers| {
+                    providers
+                        .iter()
+                        .map(|name| name.trim().to_ascii_lowercase())
+                        .filter(|name| !name.is_empty())
+                        .collect()
+                })
+                .unwrap_or(defaults.providers),
+            min_agreeing: parse_env_var(&mut report, "REFERENCE_MIN_AGREEING")
+                .unwrap_or(defaults.min_agreeing),
+            max_deviation: parse_env_var(&mut report, "REFERENCE_MAX_DEVIATION")
+                .unwrap_or(defaults.max_deviation),
+            cache_bucket: parse_env_var(&mut report, "REFERENCE_CACHE_BUCKET")
+                .unwrap_or(defaults.cache_bucket),
+            cache_ttl: parse_env_var(&mut report, "REFERENCE_CACHE_TTL")
+                .unwrap_or(defaults.cache_ttl),
+        };
+        if settings.providers.is_empty() {
+            report.fatal(
+                None,
+                "REFERENCE_PROVIDERS",
+                "REFERENCE_PROVIDERS must not be empty",
+            );
+        }
+        for name in &settings.providers {
+            if !REFERENCE_PROVIDERS.contains(&name.as_str()) {
+                report.fatal(
+                    None,
+                    "REFERENCE_PROVIDERS",
+                    format!(
+                        "unknown reference provider {name}, expected one of {}",
+                        REFERENCE_PROVIDERS.join(", ")
+                    ),
+                );
+            }
+        }
+        if settings.cache_bucket == 0 {
+            report.fatal(
+                None,
+                "REFERENCE_CACHE_BUCKET",
+                "REFERENCE_CACHE_BUCKET must be positive",
+            );
+        }
+
+        match report.is_fatal() {
+            true => Err(report),
+            false => Ok(settings),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct Network {
     pub name: NetworkName,
     pub provider: Arc<JsonRpcClient<HttpTransport>>,
+    pub chain_id: Felt,
     pub oracle_address: Felt,
     pub publisher_registry_address: Felt,
+    /// Base url of the Pragma API, the API is not monitored when not set.
+    pub api_url: Option<String>,
+    /// Base url of the sequencer gateway, the sequencer is not monitored when not set.
+    pub sequencer_url: Option<String>,
+    /// Table of the VRF requests, the VRF is not monitored when not set.
+    pub vrf_table: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +366,7 @@ pub struct DataInfo {
     pub pairs: Vec<String>,
     pub sources: HashMap<String, Vec<String>>,
     pub decimals: HashMap<String, u32>,
+    pub settings: HashMap<String, PairSettings>,
     pub table_name: String,
 }
 
@@ -61,71 +377,239 @@ pub struct Config {
     publishers: HashMap<String, Felt>,
     network: Network,
     indexer_url: String,
+    checkpoint_table_name: String,
+    syncing_policy: SyncingPolicy,
+    reference_ids: HashMap<String, String>,
+    registry: RegistryState,
+    report: ValidationReport,
+}
+
+/// Configs of all the monitored networks.
+#[derive(Debug, Clone)]
+pub struct Configs {
+    networks: Vec<Arc<Config>>,
+    intervals: Intervals,
+    report: ValidationReport,
 }
 
-/// We are using `ArcSwap` as it allow us to replace the new `Config` with
+/// We are using `ArcSwap` as it allow us to replace the new `Configs` with
 /// a new one which is required when running test cases. This approach was
 /// inspired from here - https://github.com/matklad/once_cell/issues/127
 #[allow(unused)]
-pub static CONFIG: OnceCell<ArcSwap<Config>> = OnceCell::const_new();
+pub static CONFIG: OnceCell<ArcSwap<Configs>> = OnceCell::const_new();
+
+#[allow(unused)]
+impl Configs {
+    /// Builds the configs of the given networks, failing if any of them has fatal issues.
+    pub async fn new(
+        config_inputs: Vec<ConfigInput>,
+        intervals: Intervals,
+    ) -> Result<Self, ValidationReport> {
+        let results = futures::future::join_all(config_inputs.into_iter().map(Config::new)).await;
+
+        let mut report = ValidationReport::default();
+        let mut networks = Vec::new();
+        for result in results {
+            match result {
+                Ok(config) => {
+                    report.extend(config.report().clone());
+                    networks.push(Arc::new(config));
+                }
+                Err(network_report) => report.extend(network_report),
+            }
+        }
+        check_reference_ids(&networks, &mut report);
+
+        if report.is_fatal() {
+            return Err(report);
+        }
+
+        Ok(Self {
+            networks,
+            intervals,
+            report,
+        })
+    }
+
+    /// Builds the configs of the networks to monitor, see `ConfigInput::all_from_env`.
+    pub async fn create_from_env() -> Result<Self, ValidationReport> {
+        let (config_inputs, intervals, mut report) = ConfigInput::all_from_env()?;
+        match Configs::new(config_inputs, intervals).await {
+            Ok(mut configs) => {
+                report.extend(configs.report);
+                configs.report = report;
+                Ok(configs)
+            }
+            Err(networks_report) => {
+                report.extend(networks_report);
+                Err(report)
+            }
+        }
+    }
+
+    pub fn networks(&self) -> &[Arc<Config>] {
+        &self.networks
+    }
+
+    pub fn network(&self, name: &NetworkName) -> Option<&Arc<Config>> {
+        self.networks
+            .iter()
+            .find(|config| config.network().name == *name)
+    }
+
+    pub fn intervals(&self) -> &Intervals {
+        &self.intervals
+    }
+
+    /// Issues found while validating the networks.
+    pub fn report(&self) -> &ValidationReport {
+        &self.report
+    }
+
+    /// Returns the id of the pair on the reference price providers (coingecko id),
+    /// looking it up in the networks monitoring it first, see `check_reference_ids`.
+    pub fn reference_id(&self, pair_id: &str) -> Option<&str> {
+        self.networks
+            .iter()
+            .filter(|config| config.sources(DataType::Spot).contains_key(pair_id))
+            .chain(self.networks.iter())
+            .find_map(|config| config.reference_id(pair_id))
+    }
+}
 
 #[allow(unused)]
 impl Config {
-    pub async fn new(config_input: ConfigInput) -> Self {
-        let indexer_url =
-            std::env::var("INDEXER_SERVICE_URL").expect("INDEXER_SERVICE_URL must be set");
+    /// Builds the config of a network, validating it against the oracle.
+    /// Invalid pairs and publishers are reported and left out of the monitoring,
+    /// the network is only rejected on fatal issues (e.g unreachable RPC).
+    pub async fn new(config_input: ConfigInput) -> Result<Self, ValidationReport> {
+        let network = config_input.network.to_string();
+        let mut report = ValidationReport::default();
+        let mut registry = RegistryState::default();
+
+        let Ok(indexer_url) = std::env::var("INDEXER_SERVICE_URL") else {
+            report.fatal(
+                None,
+                "INDEXER_SERVICE_URL",
+                "INDEXER_SERVICE_URL must be set",
+            );
+            return Err(report);
+        };
+        if config_input.api_url.is_some() && env_var("PRAGMA_API_KEY").is_none() {
+            report.fatal(
+                Some(&network),
+                "PRAGMA_API_KEY",
+                "PRAGMA_API_KEY must be set to monitor the API",
+            );
+            return Err(report);
+        }
 
         // Create RPC Client
-        let rpc_url = std::env::var("RPC_URL").expect("RPC_URL must be set");
-        let rpc_client = JsonRpcClient::new(HttpTransport::new(Url::parse(&rpc_url).unwrap()));
+        let rpc_url = match Url::parse(&config_input.rpc_url) {
+            Ok(rpc_url) => rpc_url,
+            Err(e) => {
+                report.fatal(
+                    Some(&network),
+                    "RPC_URL",
+                    format!("invalid url {}: {e}", config_input.rpc_url),
+                );
+                return Err(report);
+            }
+        };
+        let rpc_client = JsonRpcClient::new(HttpTransport::new(rpc_url));
+
+        let chain_id = match config_input.chain_id {
+            Some(chain_id) => chain_id,
+            None => match rpc_client.chain_id().await {
+                Ok(chain_id) => chain_id,
+                Err(e) => {
+                    report.fatal(
+                        Some(&network),
+                        "RPC_URL",
+                        format!("failed to get the chain id: {e}"),
+                    );
+                    return Err(report);
+                }
+            },
+        };
 
-        let (publishers, publisher_registry_address) =
-            init_publishers(&rpc_client, config_input.oracle_address).await;
+        let (publishers, publisher_registry_address) = match init_publishers(
+            &rpc_client,
+            config_input.oracle_address,
+            &config_input.ignore_publishers,
+            &network,
+            &mut report,
+            &mut registry,
+        )
+        .await
+        {
+            Some(publishers) => publishers,
+            None => return Err(report),
+        };
 
-        let spot_info = init_spot_config(
+        let mut spot_info = init_data_info(
             &rpc_client,
             config_input.oracle_address,
+            DataType::Spot,
             config_input.spot_pairs.clone(),
+            &config_input.ignore_sources,
+            format!("{}spot_entry", config_input.table_prefix),
+            &network,
+            &mut report,
+            &mut registry,
         )
         .await;
+        spot_info.settings = config_input.spot_settings;
 
-        let future_info = init_future_config(
+        let mut future_info = init_data_info(
             &rpc_client,
             config_input.oracle_address,
+            DataType::Future,
             config_input.future_pairs.clone(),
+            &config_input.ignore_sources,
+            format!("{}future_entry", config_input.table_prefix),
+            &network,
+            &mut report,
+            &mut registry,
         )
         .await;
+        future_info.settings = config_input.future_settings;
+
+        let reference_ids = init_reference_ids(
+            &network,
+            &spot_info.pairs,
+            config_input.reference_ids,
+            &mut report,
+        );
+
+        if report.is_fatal() {
+            return Err(report);
+        }
 
         let data_info = vec![(DataType::Spot, spot_info), (DataType::Future, future_info)]
             .into_iter()
             .collect::<HashMap<DataType, DataInfo>>();
 
-        Self {
+        Ok(Self {
             indexer_url,
+            checkpoint_table_name: format!("{}spot_checkpoints", config_input.table_prefix),
+            syncing_policy: config_input.syncing_policy,
             publishers,
             data_info,
+            reference_ids,
+            registry,
+            report,
             network: Network {
                 name: config_input.network,
                 provider: Arc::new(rpc_client),
+                chain_id,
                 oracle_address: config_input.oracle_address,
                 publisher_registry_address,
+                api_url: config_input.api_url,
+                sequencer_url: config_input.sequencer_url,
+                vrf_table: config_input.vrf_table,
             },
-        }
-    }
-
-    pub async fn create_from_env() -> Config {
-        let network = std::env::var("NETWORK").expect("NETWORK must be set");
-        let oracle_address = std::env::var("ORACLE_ADDRESS").expect("ORACLE_ADDRESS must be set");
-        let spot_pairs = std::env::var("SPOT_PAIRS").expect("SPOT_PAIRS must be set");
-        let future_pairs = std::env::var("FUTURE_PAIRS").expect("FUTURE_PAIRS must be set");
-
-        Config::new(ConfigInput {
-            network: NetworkName::from_str(&network).expect("Invalid network name"),
-            oracle_address: Felt::from_hex_unchecked(&oracle_address),
-            spot_pairs: parse_pairs(&spot_pairs),
-            future_pairs: parse_pairs(&future_pairs),
         })
-        .await
     }
 
     pub fn sources(&self, data_type: DataType) -> &HashMap<String, Vec<String>> {
@@ -141,71 +625,639 @@ impl Config {
     }
 
     pub fn network_str(&self) -> &str {
-        self.network.name.clone().into()
+        self.network.name.as_str()
     }
 
     pub fn indexer_url(&self) -> &str {
         &self.indexer_url
     }
 
-    pub fn table_name(&self, data_type: DataType) -> String {
-        let table_name = &self.data_info.get(&data_type).unwrap().table_name;
-        match self.network.name {
-            NetworkName::Mainnet => format!("mainnet_{}", table_name),
-            NetworkName::Testnet => table_name.to_string(),
-        }
+    /// Publishers and sources read from the oracle, see `RegistryState`.
+    pub fn registry(&self) -> &RegistryState {
+        &self.registry
+    }
+
+    /// Issues found while validating the network, only warnings as the
+    /// config is not built on fatal issues.
+    pub fn report(&self) -> &ValidationReport {
+        &self.report
+    }
+
+    pub fn table_name(&self, data_type: DataType) -> &str {
+        &self.data_info.get(&data_type).unwrap().table_name
+    }
+
+    /// Name of the table of the spot checkpoints, e.g `mainnet_spot_checkpoints`.
+    pub fn checkpoint_table_name(&self) -> &str {
+        &self.checkpoint_table_name
+    }
+
+    /// What the data checks do when their indexer is not synced.
+    pub fn syncing_policy(&self) -> SyncingPolicy {
+        self.syncing_policy
+    }
+
+    /// Tables read by the monitoring, whose indexers are monitored.
+    pub fn indexed_tables(&self) -> Vec<&str> {
+        let mut tables = vec![
+            self.table_name(DataType::Spot),
+            self.table_name(DataType::Future),
+            self.checkpoint_table_name(),
+        ];
+        tables.extend(self.network.vrf_table.as_deref());
+        tables
     }
 
     pub fn all_publishers(&self) -> &HashMap<String, Felt> {
         &self.publishers
     }
+
+    /// Returns the settings of a pair, if any were configured.
+    pub fn pair_settings(&self, data_type: DataType, pair_id: &str) -> Option<&PairSettings> {
+        self.data_info
+            .get(&data_type)
+            .unwrap()
+            .settings
+            .get(pair_id)
+    }
+
+    /// Returns whether the check is enabled for the given pair.
+    pub fn is_check_enabled(&self, data_type: DataType, pair_id: &str, check: Check) -> bool {
+        self.pair_settings(data_type, pair_id)
+            .is_none_or(|settings| settings.is_enabled(check))
+    }
+
+    /// Returns the alerting thresholds of the pair, or of one of its sources.
+    pub fn thresholds(
+        &self,
+        data_type: DataType,
+        pair_id: &str,
+        source: Option<&str>,
+    ) -> ResolvedThresholds {
+        self.pair_settings(data_type, pair_id)
+            .map(|settings| settings.thresholds(source))
+            .unwrap_or_default()
+    }
+
+    /// Returns the id of the pair on the reference price providers (coingecko id).
+    pub fn reference_id(&self, pair_id: &str) -> Option<&str> {
+        self.reference_ids.get(pair_id).map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct ConfigInput {
     pub network: NetworkName,
+    pub rpc_url: String,
+    /// Chain id of the network, read from the RPC when not set.
+    pub chain_id: Option<Felt>,
     pub oracle_address: Felt,
+    /// Prefix of the network tables, e.g `mainnet_` for `mainnet_spot_entry`.
+    pub table_prefix: String,
+    pub api_url: Option<String>,
+    pub sequencer_url: Option<String>,
+    /// Table of the VRF requests, e.g `vrf_requests`.
+    pub vrf_table: Option<String>,
+    pub syncing_policy: SyncingPolicy,
     pub spot_pairs: Vec<String>,
     pub future_pairs: Vec<String>,
+    /// Reference ids overriding the default ones, see `COINGECKO_IDS`.
+    pub reference_ids: HashMap<String, String>,
+    pub spot_settings: HashMap<String, PairSettings>,
+    pub future_settings: HashMap<String, PairSettings>,
+    pub ignore_sources: Vec<String>,
+    pub ignore_publishers: Vec<String>,
+}
+
+impl ConfigInput {
+    /// Builds the config inputs of the networks to monitor, along with the polling intervals
+    /// and the warnings found. Networks are given by `NETWORKS` (or `NETWORK`), defaulting
+    /// to all the networks of the config file.
+    pub fn all_from_env() -> Result<(Vec<Self>, Intervals, ValidationReport), ValidationReport> {
+        let mut report = ValidationReport::default();
+        let file = match ConfigFile::from_env() {
+            Ok(file) => file,
+            Err(e) => {
+                report.fatal(None, "CONFIG_FILE", e);
+                return Err(report);
+            }
+        };
+
+        let mut seen = HashSet::new();
+        let mut networks = env_list("NETWORKS")
+            .or(env_list("NETWORK"))
+            .or(file.enabled_networks.clone())
+            .unwrap_or(file.networks.keys().cloned().collect());
+        networks.retain(|network| {
+            let first = seen.insert(network.to_ascii_lowercase());
+            if !first {
+                report.warning(
+                    None,
+                    "NETWORKS",
+                    format!("duplicate network {network}, ignored"),
+                );
+            }
+            first
+        });
+        if networks.is_empty() {
+            report.fatal(
+                None,
+                "NETWORKS",
+                "no network to monitor, NETWORKS must be set",
+            );
+            return Err(report);
+        }
+
+        // Unprefixed, they quietly apply to every network
+        if networks.len() > 1 {
+            for name in NETWORK_ENV_VARS
+                .iter()
+                .filter(|name| env_var(name).is_some())
+            {
+                report.warning(
+                    None,
+                    name,
+                    format!(
+                        "{name} applies to every network, prefix it with the network name \
+                         (e.g MAINNET_{name}) to only set it for one"
+                    ),
+                );
+            }
+        }
+
+        if let Err(reference_report) = ReferenceSettings::from_env() {
+            report.extend(reference_report);
+        }
+
+        let mut config_inputs = Vec::new();
+        for network in networks.iter() {
+            match ConfigInput::from_env(&file, network) {
+                Ok(config_input) => config_inputs.push(config_input),
+                Err(network_report) => report.extend(network_report),
+            }
+        }
+        if report.is_fatal() {
+            return Err(report);
+        }
+
+        Ok((config_inputs, file.intervals, report))
+    }
+
+    /// Builds the config input of a network from its block in the config file and
+    /// the environment variables, which take precedence over the file.
+    /// Variables can be prefixed by the network name (e.g `MAINNET_RPC_URL`) to only
+    /// apply to that network, unprefixed ones apply to every network.
+    /// Missing or invalid settings are reported as fatal issues.
+    pub fn from_env(file: &ConfigFile, network: &str) -> Result<Self, ValidationReport> {
+        let network_file = file.network(network);
+        let mut report = ValidationReport::default();
+        let env_var = |name: &str| network_env_var(network, name);
+        let env_list = |name: &str| env_var(name).map(|list| parse_pairs(&list));
+
+        let network = NetworkName::new(network);
+        let defaults = NetworkDefaults::of(&network);
+
+        let rpc_url = required(
+            &mut report,
+            &network,
+            "RPC_URL",
+            env_var("RPC_URL").or(network_file.rpc_url),
+        );
+        let oracle_address = required(
+            &mut report,
+            &network,
+            "ORACLE_ADDRESS",
+            env_var("ORACLE_ADDRESS").or(network_file.oracle_address),
+        )
+        .and_then(|oracle_address| match Felt::from_hex(&oracle_address) {
+            Ok(oracle_address) => Some(oracle_address),
+            Err(e) => {
+                report.fatal(
+                    Some(network.as_str()),
+                    "ORACLE_ADDRESS",
+                    format!("invalid address {oracle_address}: {e}"),
+                );
+                None
+            }
+        });
+
+        let spot_pairs = required(
+            &mut report,
+            &network,
+            "SPOT_PAIRS",
+            env_list("SPOT_PAIRS").or(non_empty_keys(&network_file.spot_pairs)),
+        );
+        let future_pairs = required(
+            &mut report,
+            &network,
+            "FUTURE_PAIRS",
+            env_list("FUTURE_PAIRS").or(non_empty_keys(&network_file.future_pairs)),
+        );
+
+        let mut reference_ids = network_file
+            .spot_pairs
+            .iter()
+            .filter_map(|(pair, settings)| Some((pair.clone(), settings.reference_id.clone()?)))
+            .collect::<HashMap<String, String>>();
+        reference_ids.extend(parse_reference_ids(
+            &env_var("REFERENCE_IDS").unwrap_or_default(),
+        ));
+
+        let chain_id = match env_var("CHAIN_ID").or(network_file.chain_id) {
+            Some(chain_id) => match parse_chain_id(&chain_id) {
+                Some(chain_id) => Some(chain_id),
+                None => {
+                    report.fatal(
+                        Some(network.as_str()),
+                        "CHAIN_ID",
+                        format!("invalid chain id {chain_id}"),
+                    );
+                    None
+                }
+            },
+            None => defaults.chain_id,
+        };
+        let syncing_policy = match env_var("SYNCING_POLICY") {
+            Some(policy) => match policy.parse() {
+                Ok(policy) => policy,
+                Err(e) => {
+                    report.fatal(Some(network.as_str()), "SYNCING_POLICY", e);
+                    SyncingPolicy::default()
+                }
+            },
+            None => network_file.syncing_policy.unwrap_or_default(),
+        };
+        let sequencer_url = env_var("SEQUENCER_URL")
+            .or(network_file.sequencer_url)
+            .or(defaults.sequencer_url.map(str::to_string));
+
+        let (Some(rpc_url), Some(oracle_address), Some(spot_pairs), Some(future_pairs)) =
+            (rpc_url, oracle_address, spot_pairs, future_pairs)
+        else {
+            return Err(report);
+        };
+        if report.is_fatal() {
+            return Err(report);
+        }
+
+        Ok(ConfigInput {
+            network,
+            rpc_url,
+            chain_id,
+            oracle_address,
+            table_prefix: env_var("TABLE_PREFIX")
+                .or(network_file.table_prefix)
+                .unwrap_or(defaults.table_prefix.to_string()),
+            api_url: env_var("API_URL")
+                .or(network_file.api_url)
+                .or(defaults.api_url.map(str::to_string)),
+            sequencer_url,
+            vrf_table: env_var("VRF_TABLE")
+                .or(network_file.vrf_table)
+                .or(defaults.vrf_table.map(str::to_string)),
+            syncing_policy,
+            spot_pairs,
+            future_pairs,
+            reference_ids,
+            spot_settings: network_file.spot_pairs,
+            future_settings: network_file.future_pairs,
+            ignore_sources: env_list("IGNORE_SOURCES").unwrap_or(network_file.ignore_sources),
+            ignore_publishers: env_list("IGNORE_PUBLISHERS")
+                .unwrap_or(network_file.ignore_publishers),
+        })
+    }
+}
+
+/// Returns the value of a required setting, reporting a fatal issue when not set.
+fn required<T>(
+    report: &mut ValidationReport,
+    network: &NetworkName,
+    name: &str,
+    value: Option<T>,
+) -> Option<T> {
+    if value.is_none() {
+        report.fatal(Some(network.as_str()), name, format!("{name} must be set"));
+    }
+    value
+}
+
+/// Settings of the well-known networks, used when not configured.
+struct NetworkDefaults {
+    chain_id: Option<Felt>,
+    table_prefix: &'static str,
+    api_url: Option<&'static str>,
+    sequencer_url: Option<&'static str>,
+    vrf_table: Option<&'static str>,
+}
+
+impl NetworkDefaults {
+    fn of(network: &NetworkName) -> Self {
+        match network.as_str() {
+            NetworkName::MAINNET => Self {
+                chain_id: Some(chain_id::MAINNET),
+                table_prefix: "mainnet_",
+                api_url: Some("https://api.prod.pragma.build/node/v1"),
+                sequencer_url: Some("https://alpha-mainnet.starknet.io"),
+                vrf_table: None,
+            },
+            NetworkName::TESTNET => Self {
+                chain_id: Some(chain_id::SEPOLIA),
+                table_prefix: "",
+                api_url: Some("https://api.dev.pragma.build/node/v1"),
+                sequencer_url: Some("https://alpha-sepolia.starknet.io"),
+                vrf_table: Some("vrf_requests"),
+            },
+            _ => Self {
+                chain_id: None,
+                table_prefix: "",
+                api_url: None,
+                sequencer_url: None,
+                vrf_table: None,
+            },
+        }
+    }
+}
+
+/// Parses a chain id given either as hex or as a short string, e.g `SN_MAIN`.
+fn parse_chain_id(chain_id: &str) -> Option<Felt> {
+    match chain_id.starts_with("0x") {
+        true => Felt::from_hex(chain_id).ok(),
+        false => cairo_short_string_to_felt(chain_id).ok(),
+    }
+}
+
+/// Config file given by the `CONFIG_FILE` env variable.
+/// e.g
+/// ```toml
+/// enabled_networks = ["testnet"]
+///
+/// [intervals]
+/// monitor = 30
+///
+/// [networks.testnet]
+/// rpc_url = "https://starknet-sepolia.public.blastapi.io"
+/// oracle_address = "0x06df335982dddce41008e4c03f2546fa27276567b5274c7d0c1262f3c2b5d167"
+/// ignore_sources = ["BITSTAMP"]
+///
+/// [networks.testnet.spot_pairs."BTC/USD"]
+/// reference_id = "bitcoin"
+/// thresholds = { price_deviation = 0.025 }
+///
+/// [networks.testnet.future_pairs."BTC/USD"]
+/// checks = ["source_deviation", "on_off_deviation"]
+/// ```
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Networks to monitor, all of them when not set. Overridden by `NETWORKS`.
+    pub enabled_networks: Option<Vec<String>>,
+    pub intervals: Intervals,
+    pub networks: HashMap<String, NetworkConfigFile>,
+}
+
+/// Network block of the config file.
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfigFile {
+    pub rpc_url: Option<String>,
+    /// Chain id, as hex or short string. Read from the RPC when not set.
+    pub chain_id: Option<String>,
+    pub oracle_address: Option<String>,
+    /// Prefix of the tables filled by the indexers, e.g `mainnet_`.
+    pub table_prefix: Option<String>,
+    /// Base url of the Pragma API.
+    pub api_url: Option<String>,
+    /// Base url of the sequencer gateway, e.g `https://alpha-mainnet.starknet.io`.
+    pub sequencer_url: Option<String>,
+    /// Table of the VRF requests, e.g `vrf_requests`.
+    pub vrf_table: Option<String>,
+    /// What the data checks do when their indexer is not synced, see `SyncingPolicy`.
+    pub syncing_policy: Option<SyncingPolicy>,
+    pub spot_pairs: HashMap<String, PairSettings>,
+    pub future_pairs: HashMap<String, PairSettings>,
+    pub ignore_sources: Vec<String>,
+    pub ignore_publishers: Vec<String>,
+}
+
+impl ConfigFile {
+    /// Loads the config file given by `CONFIG_FILE`, if any.
+    pub fn from_env() -> Result<Self, String> {
+        match env_var("CONFIG_FILE") {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {path}: {e}"))?;
+        Self::parse(&content).map_err(|e| format!("Invalid config file {path}: {e}"))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+
+    /// Returns the block of the given network, network names being case insensitive.
+    pub fn network(&self, name: &str) -> NetworkConfigFile {
+        self.networks
+            .iter()
+            .find(|(network, _)| network.eq_ignore_ascii_case(name))
+            .map(|(_, network)| network.clone())
+            .unwrap_or_default()
+    }
 }
 
+/// Variables identifying the chain or the data of a network, which are rarely
+/// shared by several networks, see `ConfigInput::all_from_env`.
+const NETWORK_ENV_VARS: [&str; 9] = [
+    "RPC_URL",
+    "CHAIN_ID",
+    "ORACLE_ADDRESS",
+    "TABLE_PREFIX",
+    "API_URL",
+    "SEQUENCER_URL",
+    "VRF_TABLE",
+    "SPOT_PAIRS",
+    "FUTURE_PAIRS",
+];
+
+/// Reads an env variable, empty values being considered as not set.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Reads an env variable of a network, e.g `MAINNET_RPC_URL`, falling back to `RPC_URL`.
+fn network_env_var(network: &str, name: &str) -> Option<String> {
+    env_var(&format!("{}_{name}", network.to_ascii_uppercase())).or(env_var(name))
+}
+
+/// Parses an env variable, if set. Invalid values are reported as fatal issues.
+fn parse_env_var<T>(report: &mut ValidationReport, name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value = env_var(name)?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            report.fatal(None, name, format!("invalid value {value}: {e}"));
+            None
+        }
+    }
+}
+
+/// Reads a comma separated list from an env variable, if set.
+fn env_list(name: &str) -> Option<Vec<String>> {
+    env_var(name).map(|list| parse_pairs(&list))
+}
+
+fn non_empty_keys(pairs: &HashMap<String, PairSettings>) -> Option<Vec<String>> {
+    (!pairs.is_empty()).then(|| pairs.keys().cloned().collect())
+}
+
+/// Returns the configs of all the monitored networks.
+/// When not initialized yet, the config of the given network is used, if any.
 #[allow(unused)]
-pub async fn get_config(config_input: Option<ConfigInput>) -> Guard<Arc<Config>> {
+pub async fn get_config(config_input: Option<ConfigInput>) -> Guard<Arc<Configs>> {
     let cfg = CONFIG
         .get_or_init(|| async {
-            match config_input {
-                Some(config_input) => ArcSwap::from_pointee(Config::new(config_input).await),
-                None => ArcSwap::from_pointee(Config::create_from_env().await),
-            }
+            let configs = match config_input {
+                Some(config_input) => Configs::new(vec![config_input], Intervals::default()).await,
+                None => Configs::create_from_env().await,
+            };
+            let configs =
+                configs.unwrap_or_else(|report| panic!("Invalid configuration\n{report}"));
+            record_config_load();
+            ArcSwap::from_pointee(configs)
         })
         .await;
     cfg.load()
 }
 
-/// This function is used to periodically update the configuration settings
-/// from the environment variables. This is useful when we want to update the
-/// configuration settings without restarting the service.
+/// Initializes the configs of the monitored networks with already validated ones.
+#[allow(unused)]
+pub async fn init_config(configs: Configs) -> Guard<Arc<Configs>> {
+    CONFIG
+        .get_or_init(|| async {
+            record_config_load();
+            ArcSwap::from_pointee(configs)
+        })
+        .await
+        .load()
+}
+
+/// Reloads the config periodically, on SIGHUP and when the config file changes.
+/// The monitoring loops read the config on each tick, so they pick up new
+/// pairs/publishers without restarting the service.
 #[allow(unused)]
-pub async fn periodic_config_update() {
-    let interval = Duration::from_secs(CONFIG_UPDATE_INTERVAL); // Set the update interval as needed (3 hours in this example)
+pub async fn watch_config_updates() {
+    let period = Duration::from_secs(get_config(None).await.intervals().config_update);
+    let mut periodic = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    let mut file_check = tokio::time::interval(Duration::from_secs(CONFIG_FILE_CHECK_INTERVAL));
+    let mut hangup = signal(SignalKind::hangup()).expect("Failed to listen to SIGHUP");
 
-    let mut next_update = Instant::now() + interval;
+    let mut config_file = read_config_file();
 
     loop {
-        let new_config = Config::create_from_env().await;
-        let updated_config = ArcSwap::from_pointee(new_config.clone());
+        let reason = tokio::select! {
+            _ = periodic.tick() => "periodic update",
+            _ = hangup.recv() => "SIGHUP",
+            _ = file_check.tick() => {
+                let content = read_config_file();
+                if content == config_file {
+                    continue;
+                }
+                config_file = content;
+                "config file change"
+            }
+        };
+
+        reload_config(reason).await;
+    }
+}
+
+/// Rebuilds the configs from the environment variables and the config file.
+/// The current configs are kept when the new ones are invalid, or when they
+/// require a restart, see `restart_required_changes`.
+pub async fn reload_config(reason: &str) {
+    match Configs::create_from_env().await {
+        Ok(new_config) => {
+            if !new_config.report().is_empty() {
+                log::warn!("{}", new_config.report());
+            }
 
-        let current_config_cell = CONFIG.get_or_init(|| async { updated_config }).await;
+            if let Some(current_config) = CONFIG.get() {
+                let changes = restart_required_changes(&current_config.load(), &new_config);
+                if !changes.is_empty() {
+                    log::error!(
+                        "[CONFIG] Failed to reload the config ({reason}), a restart is required to apply: {}",
+                        changes.join(", ")
+                    );
+                    return;
+                }
+
+                // Compare the registries with the ones of the current config
+                for config in new_config.networks() {
+                    if let Some(current) = current_config.load().network(&config.network().name) {
+                        let changes = config.registry().changes_since(current.registry());
+                        report_changes(config.network_str(), &changes);
+                    }
+                }
+            }
 
-        // Store the updated config in the ArcSwap
-        current_config_cell.store(new_config.into());
+            let new_config = Arc::new(new_config);
+            let current_config_cell = CONFIG
+                .get_or_init(|| async { ArcSwap::new(new_config.clone()) })
+                .await;
 
-        tokio::time::sleep_until(next_update.into()).await;
+            // Store the updated config in the ArcSwap
+            current_config_cell.store(new_config);
+            record_config_load();
 
-        next_update += interval;
+            log::info!("[CONFIG] Config reloaded ({reason})");
+        }
+        // Keep monitoring with the current config
+        Err(report) => log::error!("[CONFIG] Failed to reload the config ({reason}): {report}"),
     }
 }
 
+/// Returns the changes of the configs that are only applied on restart, as the monitoring
+/// tasks are spawned at startup: new networks, API or VRF monitoring, and polling intervals.
+/// Removed networks and monitoring are applied, the tasks skipping their ticks.
+fn restart_required_changes(current: &Configs, new: &Configs) -> Vec<String> {
+    let mut changes = Vec::new();
+    for config in new.networks() {
+        let network = &config.network().name;
+        let Some(current) = current.network(network) else {
+            changes.push(format!("new network {network}"));
+            continue;
+        };
+        if current.network().api_url.is_none() && config.network().api_url.is_some() {
+            changes.push(format!("API_URL of {network}"));
+        }
+        if current.network().vrf_table.is_none() && config.network().vrf_table.is_some() {
+            changes.push(format!("VRF_TABLE of {network}"));
+        }
+    }
+    if current.intervals() != new.intervals() {
+        changes.push("intervals".to_string());
+    }
+    changes
+}
+
+/// Records a successful load of the config in the metrics.
+fn record_config_load() {
+    CONFIG_GENERATION.inc();
+    CONFIG_LAST_RELOAD_TIMESTAMP.set(chrono::Utc::now().timestamp() as f64);
+}
+
+/// Reads the content of the config file, to detect its changes.
+fn read_config_file() -> Option<String> {
+    env_var("CONFIG_FILE").and_then(|path| std::fs::read_to_string(path).ok())
+}
+
 /// OnceCell only allows us to initialize the config once and that's how it should be on production.
 /// However, when running tests, we often want to reinitialize because we want to clear the DB and
 /// set it up again for reuse in new tests. By calling `config_force_init` we replace the already
@@ -214,19 +1266,30 @@ pub async fn periodic_config_update() {
 #[cfg(test)]
 pub async fn config_force_init(config_input: ConfigInput) {
     match CONFIG.get() {
-        Some(arc) => arc.store(Arc::new(Config::new(config_input).await)),
+        Some(arc) => arc.store(Arc::new(
+            Configs::new(vec![config_input], Intervals::default())
+                .await
+                .unwrap_or_else(|report| panic!("Invalid configuration\n{report}")),
+        )),
         None => {
             get_config(Some(config_input)).await;
         }
     };
 }
 
+/// Fetches the publishers of the registry of the oracle, along with their address.
+/// Publishers that cannot be resolved are reported and skipped, `None` is returned
+/// (with a fatal issue) when the registry cannot be read.
 async fn init_publishers(
     rpc_client: &JsonRpcClient<HttpTransport>,
     oracle_address: Felt,
-) -> (HashMap<String, Felt>, Felt) {
+    excluded_publishers: &[String],
+    network: &str,
+    report: &mut ValidationReport,
+    registry: &mut RegistryState,
+) -> Option<(HashMap<String, Felt>, Felt)> {
     // Fetch publisher registry address
-    let publisher_registry_address = *rpc_client
+    let publisher_registry_address = match rpc_client
         .call(
             FunctionCall {
                 contract_address: oracle_address,
@@ -236,12 +1299,29 @@ async fn init_publishers(
             BlockId::Tag(BlockTag::Latest),
         )
         .await
-        .expect("failed to call contract")
-        .first()
-        .unwrap();
+        .map(|result| result.first().copied())
+    {
+        Ok(Some(address)) => address,
+        Ok(None) => {
+            report.fatal(
+                Some(network),
+                "ORACLE_ADDRESS",
+                format!("{oracle_address:#x} returned no publisher registry"),
+            );
+            return None;
+        }
+        Err(e) => {
+            report.fatal(
+                Some(network),
+                "ORACLE_ADDRESS",
+                format!("failed to get the publisher registry of {oracle_address:#x}: {e}"),
+            );
+            return None;
+        }
+    };
 
     // Fetch publishers
-    let publishers: Vec<String> = rpc_client
+    let publishers = match rpc_client
         .call(
             FunctionCall {
                 contract_address: publisher_registry_address,
@@ -251,20 +1331,38 @@ async fn init_publishers(
             BlockId::Tag(BlockTag::Latest),
         )
         .await
-        .expect("failed to get publishers")
-        .into_iter()
-        .map(|publisher| parse_cairo_short_string(&publisher).unwrap())
-        .collect();
-
-    let publishers = publishers[1..].to_vec();
+    {
+        Ok(publishers) => publishers,
+        Err(e) => {
+            report.fatal(
+                Some(network),
+                "Publisher registry",
+                format!("failed to get the publishers of {publisher_registry_address:#x}: {e}"),
+            );
+            return None;
+        }
+    };
 
-    // Exclude publishers that are not supported by the monitoring service
-    let excluded_publishers = std::env::var("IGNORE_PUBLISHERS")
-        .unwrap_or("".to_string())
-        .split(',')
-        .map(|publisher| publisher.to_string())
+    // Remove the length of the array
+    let publishers = publishers
+        .iter()
+        .skip(1)
+        .filter_map(|publisher| match parse_cairo_short_string(publisher) {
+            Ok(publisher) => Some(publisher),
+            Err(e) => {
+                report.warning(
+                    Some(network),
+                    &format!("Publisher {publisher:#x}"),
+                    format!("invalid publisher name, skipped: {e}"),
+                );
+                None
+            }
+        })
         .collect::<Vec<String>>();
 
+    registry.publishers.extend(publishers.iter().cloned());
+
+    // Exclude publishers that are not supported by the monitoring service
     let publishers = publishers
         .into_iter()
         .filter(|publisher| !excluded_publishers.contains(publisher))
@@ -272,168 +1370,258 @@ async fn init_publishers(
 
     let mut publishers_map: HashMap<String, Felt> = HashMap::new();
     for publisher in publishers {
-        let field_publisher =
-            cairo_short_string_to_felt(&publisher).expect("Failed to parse publisher");
-        let publisher_address = *rpc_client
+        let subject = format!("Publisher {publisher}");
+        let field_publisher = match cairo_short_string_to_felt(&publisher) {
+            Ok(field_publisher) => field_publisher,
+            Err(e) => {
+                report.warning(
+                    Some(network),
+                    &subject,
+                    format!("invalid name, skipped: {e}"),
+                );
+                continue;
+            }
+        };
+        let publisher_address = rpc_client
             .call(
                 FunctionCall {
                     contract_address: publisher_registry_address,
-                    entry_point_selector: selector!("get_publisher_address"), // Replace with actual function name
+                    entry_point_selector: selector!("get_publisher_address"),
                     calldata: vec![field_publisher],
                 },
                 BlockId::Tag(BlockTag::Latest),
             )
             .await
-            .expect("failed to get publisher address")
-            .first()
-            .unwrap();
+            .map(|result| result.first().copied());
 
-        publishers_map.insert(publisher, publisher_address);
+        match publisher_address {
+            Ok(Some(address)) if address != Felt::ZERO => {
+                publishers_map.insert(publisher, address);
+            }
+            Ok(_) => report.warning(
+                Some(network),
+                &subject,
+                "no address in the publisher registry, skipped",
+            ),
+            Err(e) => report.warning(
+                Some(network),
+                &subject,
+                format!("failed to get the address, skipped: {e}"),
+            ),
+        }
     }
-    (publishers_map, publisher_registry_address)
+    Some((publishers_map, publisher_registry_address))
 }
 
-async fn init_spot_config(
+/// Fetches the decimals and sources of the pairs from the oracle.
+/// Pairs that are not registered on the oracle (no decimals or no sources)
+/// are reported and left out, which is fatal when none of them is valid.
+#[allow(clippy::too_many_arguments)]
+async fn init_data_info(
     rpc_client: &JsonRpcClient<HttpTransport>,
     oracle_address: Felt,
+    data_type: DataType,
     pairs: Vec<String>,
+    excluded_sources: &[String],
+    table_name: String,
+    network: &str,
+    report: &mut ValidationReport,
+    registry: &mut RegistryState,
 ) -> DataInfo {
+    let mut valid_pairs = Vec::new();
     let mut sources: HashMap<String, Vec<String>> = HashMap::new();
     let mut decimals: HashMap<String, u32> = HashMap::new();
 
-    let excluded_sources = std::env::var("IGNORE_SOURCES")
-        .unwrap_or("".to_string())
-        .split(',')
-        .map(|source| source.to_string())
-        .collect::<Vec<String>>();
-
-    for pair in pairs.clone() {
-        let field_pair = cairo_short_string_to_felt(&pair).unwrap();
+    for pair in pairs.iter() {
+        let subject = format!("{data_type} pair {pair}");
+        let field_pair = match cairo_short_string_to_felt(pair) {
+            Ok(field_pair) => field_pair,
+            Err(e) => {
+                report.warning(Some(network), &subject, format!("invalid pair id: {e}"));
+                continue;
+            }
+        };
+        let calldata = match data_type {
+            DataType::Spot => vec![Felt::ZERO, field_pair],
+            DataType::Future => vec![Felt::ONE, field_pair, Felt::ZERO],
+        };
 
         // Fetch decimals
-        let spot_decimals = *rpc_client
+        let pair_decimals = match rpc_client
             .call(
                 FunctionCall {
                     contract_address: oracle_address,
                     entry_point_selector: selector!("get_decimals"),
-                    calldata: vec![Felt::ZERO, field_pair],
+                    calldata: calldata.clone(),
                 },
                 BlockId::Tag(BlockTag::Latest),
             )
             .await
-            .expect("failed to get decimals")
-            .first()
-            .unwrap();
-
-        decimals.insert(pair.to_string(), try_felt_to_u32(&spot_decimals).unwrap());
+        {
+            Ok(result) => result
+                .first()
+                .and_then(|decimals| try_felt_to_u32(decimals).ok()),
+            Err(e) => {
+                report.warning(
+                    Some(network),
+                    &subject,
+                    format!("failed to get decimals: {e}"),
+                );
+                continue;
+            }
+        };
+        let pair_decimals = match pair_decimals {
+            Some(pair_decimals) if pair_decimals > 0 => pair_decimals,
+            _ => {
+                report.warning(
+                    Some(network),
+                    &subject,
+                    "not registered on the oracle (no decimals)",
+                );
+                continue;
+            }
+        };
 
         // Fetch sources
-        let spot_pair_sources = rpc_client
+        let oracle_sources = match rpc_client
             .call(
                 FunctionCall {
                     contract_address: oracle_address,
                     entry_point_selector: selector!("get_all_sources"),
-                    calldata: vec![Felt::ZERO, field_pair],
+                    calldata,
                 },
                 BlockId::Tag(BlockTag::Latest),
             )
             .await
-            .expect("failed to get pair sources");
+        {
+            Ok(oracle_sources) => oracle_sources,
+            Err(e) => {
+                report.warning(
+                    Some(network),
+                    &subject,
+                    format!("failed to get sources: {e}"),
+                );
+                continue;
+            }
+        };
 
-        // Store all sources for the given pair
+        // Store all sources for the given pair, without the length of the array
         let mut pair_sources = Vec::new();
-
-        // Remove first elements of sources' arrays
-        let spot_pair_sources = spot_pair_sources[1..].to_vec();
-        // let future_pair_sources = future_pair_sources[1..].to_vec();
-
-        for source in spot_pair_sources {
-            let source = parse_cairo_short_string(&source).unwrap();
+        let registry_sources = registry
+            .sources
+            .entry((data_type.clone(), pair.to_string()))
+            .or_default();
+        for source in oracle_sources.iter().skip(1) {
+            let Ok(source) = parse_cairo_short_string(source) else {
+                report.warning(
+                    Some(network),
+                    &subject,
+                    format!("invalid source {source:#x}, skipped"),
+                );
+                continue;
+            };
+            registry_sources.insert(source.clone());
             if !pair_sources.contains(&source) && !excluded_sources.contains(&source) {
                 pair_sources.push(source);
             }
         }
 
+        if pair_sources.is_empty() {
+            let message = match oracle_sources.len() > 1 {
+                true => "all the sources are ignored",
+                false => "no sources on the oracle",
+            };
+            report.warning(Some(network), &subject, message);
+            continue;
+        }
+
+        decimals.insert(pair.to_string(), pair_decimals);
         sources.insert(pair.to_string(), pair_sources);
+        valid_pairs.push(pair.to_string());
+    }
+
+    if !pairs.is_empty() && valid_pairs.is_empty() {
+        report.fatal(
+            Some(network),
+            &format!("{}_PAIRS", data_type.to_string().to_uppercase()),
+            format!("none of the {data_type} pairs can be monitored"),
+        );
     }
 
     DataInfo {
         decimals,
-        pairs,
+        pairs: valid_pairs,
         sources,
-        table_name: "spot_entry".to_string(),
+        settings: HashMap::new(),
+        table_name,
     }
 }
 
-async fn init_future_config(
-    rpc_client: &JsonRpcClient<HttpTransport>,
-    oracle_address: Felt,
-    pairs: Vec<String>,
-) -> DataInfo {
-    let mut sources: HashMap<String, Vec<String>> = HashMap::new();
-    let mut decimals: HashMap<String, u32> = HashMap::new();
-
-    let excluded_sources = std::env::var("IGNORE_SOURCES")
-        .unwrap_or("".to_string())
-        .split(',')
-        .map(|source| source.to_string())
-        .collect::<Vec<String>>();
-
-    for pair in pairs.clone() {
-        let field_pair = cairo_short_string_to_felt(&pair).unwrap();
-
-        // Fetch decimals
-        let future_decimals = *rpc_client
-            .call(
-                FunctionCall {
-                    contract_address: oracle_address,
-                    entry_point_selector: selector!("get_decimals"),
-                    calldata: vec![Felt::ONE, field_pair, Felt::ZERO],
-                },
-                BlockId::Tag(BlockTag::Latest),
-            )
-            .await
-            .expect("failed to get decimals")
-            .first()
-            .unwrap();
-
-        decimals.insert(pair.to_string(), try_felt_to_u32(&future_decimals).unwrap());
-
-        // Fetch sources
-        let future_pair_sources = rpc_client
-            .call(
-                FunctionCall {
-                    contract_address: oracle_address,
-                    entry_point_selector: selector!("get_all_sources"),
-                    calldata: vec![Felt::ONE, field_pair, Felt::ZERO],
-                },
-                BlockId::Tag(BlockTag::Latest),
-            )
-            .await
-            .expect("failed to get pair sources");
-
-        // Store all sources for the given pair
-        let mut pair_sources = Vec::new();
+/// Builds the mapping from pairs to reference ids, using `COINGECKO_IDS` as defaults.
+/// Pairs without a reference id are reported with a warning and the `missing_reference_id` metric.
+fn init_reference_ids(
+    network: &str,
+    pairs: &[String],
+    overrides: HashMap<String, String>,
+    report: &mut ValidationReport,
+) -> HashMap<String, String> {
+    let mut reference_ids = COINGECKO_IDS
+        .entries()
+        .map(|(pair, id)| (pair.to_string(), id.to_string()))
+        .collect::<HashMap<String, String>>();
+    reference_ids.extend(overrides);
+
+    for pair in pairs {
+        let missing = !reference_ids.contains_key(pair);
+        if missing {
+            report.warning(
+                Some(network),
+                &format!("Spot pair {pair}"),
+                "no reference id, its price deviation will not be monitored",
+            );
+        }
+        MISSING_REFERENCE_ID
+            .with_label_values(&[network, pair])
+            .set(missing as i64);
+    }
 
-        // Remove first elements of sources' arrays
-        let future_pair_sources = future_pair_sources[1..].to_vec();
+    reference_ids
+}
 
-        for source in future_pair_sources {
-            let source = parse_cairo_short_string(&source).unwrap();
-            if !pair_sources.contains(&source) && !excluded_sources.contains(&source) {
-                pair_sources.push(source);
+/// Rejects the pairs mapped to different reference ids by the networks monitoring them,
+/// as the reference prices are shared by all the networks.
+fn check_reference_ids(networks: &[Arc<Config>], report: &mut ValidationReport) {
+    let mut reference_ids = HashMap::<&str, (&str, &str)>::new();
+    for config in networks {
+        for pair in config.sources(DataType::Spot).keys() {
+            let Some(reference_id) = config.reference_id(pair) else {
+                continue;
+            };
+            let (network, first_id) = *reference_ids
+                .entry(pair)
+                .or_insert((config.network_str(), reference_id));
+            if first_id != reference_id {
+                report.fatal(
+                    Some(config.network_str()),
+                    &format!("Spot pair {pair}"),
+                    format!(
+                        "reference id {reference_id} conflicts with {first_id} on {network}, \
+                         the reference ids must be the same on all networks"
+                    ),
+                );
             }
         }
-
-        sources.insert(pair.to_string(), pair_sources);
     }
+}
 
-    DataInfo {
-        decimals,
-        pairs,
-        sources,
-        table_name: "future_entry".to_string(),
-    }
+/// Parse reference ids from a comma separated list of `pair:id`.
+/// e.g BTC/USD:bitcoin,ETH/USD:ethereum
+pub fn parse_reference_ids(reference_ids: &str) -> HashMap<String, String> {
+    reference_ids
+        .split(',')
+        .filter_map(|entry| entry.split_once(':'))
+        .map(|(pair, id)| (pair.trim().to_string(), id.trim().to_string()))
+        .collect()
 }
 
 /// Parse pairs from a comma separated string.
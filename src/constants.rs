@@ -1,6 +1,9 @@
 use lazy_static::lazy_static;
 use phf::phf_map;
-use prometheus::{opts, register_gauge_vec, register_int_gauge_vec, GaugeVec, IntGaugeVec};
+use prometheus::{
+    opts, register_gauge, register_gauge_vec, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Gauge, GaugeVec, IntCounterVec, IntGauge, IntGaugeVec,
+};
 
 #[allow(unused)]
 pub(crate) static COINGECKO_IDS: phf::Map<&'static str, &'static str> = phf_map! {
@@ -45,7 +48,7 @@ lazy_static! {
             "price_deviation",
             "Price deviation from the reference price."
         ),
-        &["network", "pair", "source", "type"]
+        &["network", "pair", "source", "type", "reference"]
     )
     .unwrap();
     pub static ref PRICE_DEVIATION_SOURCE: GaugeVec = register_gauge_vec!(
@@ -69,7 +72,31 @@ lazy_static! {
             "indexer_blocks_left",
             "Number of blocks left to index for a given indexer."
         ),
-        &["network", "type"]
+        &["network", "table"]
+    )
+    .unwrap();
+    pub static ref INDEXER_LAST_BLOCK_TIMESTAMP: GaugeVec = register_gauge_vec!(
+        opts!(
+            "indexer_last_block_timestamp_seconds",
+            "Unix timestamp of the last block indexed in the table."
+        ),
+        &["network", "table"]
+    )
+    .unwrap();
+    pub static ref INDEXER_DEGRADED: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "indexer_degraded",
+            "Whether the status of the table is measured from the database as the indexer service is unavailable (1) or not (0)."
+        ),
+        &["network", "table"]
+    )
+    .unwrap();
+    pub static ref INDEXER_STATUS: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "indexer_status",
+            "Status of the indexer of the table, as reported by the indexer service."
+        ),
+        &["network", "table", "reason"]
     )
     .unwrap();
     pub static ref PUBLISHER_BALANCE: GaugeVec = register_gauge_vec!(
@@ -82,7 +109,7 @@ lazy_static! {
             "api_price_deviation",
             "Price deviation from the reference price."
         ),
-        &["network", "pair"]
+        &["network", "pair", "reference"]
     )
     .unwrap();
     pub static ref ON_OFF_PRICE_DEVIATION: GaugeVec = register_gauge_vec!(
@@ -90,7 +117,23 @@ lazy_static! {
             "on_off_price_deviation",
             "On chain price deviation from the reference price"
         ),
-        &["network", "pair", "type"]
+        &["network", "pair", "type", "reference"]
+    )
+    .unwrap();
+    pub static ref REFERENCE_PRICE_SPREAD: GaugeVec = register_gauge_vec!(
+        opts!(
+            "reference_price_spread",
+            "Relative spread between the reference prices."
+        ),
+        &["network", "pair"]
+    )
+    .unwrap();
+    pub static ref MISSING_REFERENCE_ID: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "missing_reference_id",
+            "Whether a pair has no reference id configured (1) or not (0)."
+        ),
+        &["network", "pair"]
     )
     .unwrap();
     pub static ref API_TIME_SINCE_LAST_UPDATE: GaugeVec = register_gauge_vec!(
@@ -117,6 +160,189 @@ lazy_static! {
         &["network"]
     )
     .unwrap();
+    pub static ref CONFIG_GENERATION: IntGauge = register_int_gauge!(opts!(
+        "config_generation",
+        "Number of successful loads of the configuration."
+    ))
+    .unwrap();
+    pub static ref CONFIG_LAST_RELOAD_TIMESTAMP: Gauge = register_gauge!(opts!(
+        "config_last_reload_timestamp_seconds",
+        "Unix timestamp of the last successful load of the configuration."
+    ))
+    .unwrap();
+    pub static ref THRESHOLD_PRICE_DEVIATION: GaugeVec = register_gauge_vec!(
+        opts!(
+            "threshold_price_deviation",
+            "Maximum deviation of the source price from the reference price."
+        ),
+        &["network", "pair", "source", "type"]
+    )
+    .unwrap();
+    pub static ref THRESHOLD_SOURCE_DEVIATION: GaugeVec = register_gauge_vec!(
+        opts!(
+            "threshold_source_deviation",
+            "Maximum deviation of the source price from the on-chain median."
+        ),
+        &["network", "pair", "source", "type"]
+    )
+    .unwrap();
+    pub static ref THRESHOLD_ON_OFF_DEVIATION: GaugeVec = register_gauge_vec!(
+        opts!(
+            "threshold_on_off_deviation",
+            "Maximum deviation of the on-chain median from the reference price."
+        ),
+        &["network", "pair", "type"]
+    )
+    .unwrap();
+    pub static ref THRESHOLD_TIME_SINCE_LAST_UPDATE: GaugeVec = register_gauge_vec!(
+        opts!(
+            "threshold_time_since_last_update",
+            "Maximum time since the last update of the pair in seconds."
+        ),
+        &["network", "pair", "type"]
+    )
+    .unwrap();
+    pub static ref THRESHOLD_NUM_SOURCES: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "threshold_num_sources",
+            "Minimum number of sources aggregated for the pair."
+        ),
+        &["network", "pair", "type"]
+    )
+    .unwrap();
+    pub static ref REGISTRY_PUBLISHER_ADDED: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "registry_publisher_added_total",
+            "Number of times a publisher was added to the publisher registry."
+        ),
+        &["network", "publisher"]
+    )
+    .unwrap();
+    pub static ref REGISTRY_PUBLISHER_REMOVED: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "registry_publisher_removed_total",
+            "Number of times a publisher was removed from the publisher registry."
+        ),
+        &["network", "publisher"]
+    )
+    .unwrap();
+    pub static ref REGISTRY_SOURCE_ADDED: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "registry_source_added_total",
+            "Number of times a source was added to a pair on the oracle."
+        ),
+        &["network", "pair", "source", "type"]
+    )
+    .unwrap();
+    pub static ref REGISTRY_SOURCE_REMOVED: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "registry_source_removed_total",
+            "Number of times a source was removed from a pair on the oracle."
+        ),
+        &["network", "pair", "source", "type"]
+    )
+    .unwrap();
+    pub static ref CHECKPOINT_TIME_SINCE_LAST_UPDATE: GaugeVec = register_gauge_vec!(
+        opts!(
+            "checkpoint_time_since_last_update",
+            "Time since the last checkpoint of the pair in seconds."
+        ),
+        &["network", "pair"]
+    )
+    .unwrap();
+    pub static ref CHECKPOINT_MEDIAN_DEVIATION: GaugeVec = register_gauge_vec!(
+        opts!(
+            "checkpoint_median_deviation",
+            "Deviation of the last checkpoint price from the current on-chain median."
+        ),
+        &["network", "pair"]
+    )
+    .unwrap();
+    pub static ref CHECKPOINT_PRICE_DEVIATION: GaugeVec = register_gauge_vec!(
+        opts!(
+            "checkpoint_price_deviation",
+            "Deviation of the last checkpoint price from the reference price."
+        ),
+        &["network", "pair", "reference"]
+    )
+    .unwrap();
+    pub static ref CHECKPOINT_NUM_SOURCES: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "checkpoint_num_sources",
+            "Number of sources aggregated in the last checkpoint of the pair."
+        ),
+        &["network", "pair"]
+    )
+    .unwrap();
+    pub static ref CHECKPOINT_SENDER: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "checkpoint_sender",
+            "Address that sent the last checkpoint of the pair (always 1)."
+        ),
+        &["network", "pair", "sender_address"]
+    )
+    .unwrap();
+    pub static ref MONITOR_SKIPPED_TICKS: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "monitor_skipped_ticks_total",
+            "Number of ticks of a check skipped because of the state of its indexer."
+        ),
+        &["network", "check", "reason"]
+    )
+    .unwrap();
+    pub static ref MONITOR_RUNNING_UNSYNCED: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "monitor_running_unsynced",
+            "Whether the last tick of a check ran while its indexer was not synced (1) or not (0)."
+        ),
+        &["network", "check"]
+    )
+    .unwrap();
+    pub static ref VRF_PENDING_REQUESTS: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "vrf_pending_requests",
+            "Number of pending VRF requests by age."
+        ),
+        &["network", "age"]
+    )
+    .unwrap();
+    pub static ref VRF_OLDEST_PENDING_REQUEST: GaugeVec = register_gauge_vec!(
+        opts!(
+            "vrf_oldest_pending_request_seconds",
+            "Age of the oldest pending VRF request in seconds."
+        ),
+        &["network"]
+    )
+    .unwrap();
+    pub static ref VRF_REQUESTS_PAST_MINIMUM_BLOCK: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "vrf_requests_past_minimum_block",
+            "Number of pending VRF requests whose minimum block number is past."
+        ),
+        &["network"]
+    )
+    .unwrap();
+    pub static ref VRF_FULFILLMENT_LATENCY: GaugeVec = register_gauge_vec!(
+        opts!(
+            "vrf_fulfillment_latency_seconds",
+            "Quantiles of the fulfillment latency of the recent VRF requests in seconds."
+        ),
+        &["network", "quantile"]
+    )
+    .unwrap();
+    pub static ref VRF_REQUESTS: IntGaugeVec = register_int_gauge_vec!(
+        opts!("vrf_requests", "Number of VRF requests by status."),
+        &["network", "status"]
+    )
+    .unwrap();
+    pub static ref VRF_REQUESTS_BY_REQUESTOR: IntGaugeVec = register_int_gauge_vec!(
+        opts!(
+            "vrf_requests_by_requestor",
+            "Number of VRF requests by requestor and status."
+        ),
+        &["network", "requestor", "status"]
+    )
+    .unwrap();
 }
 
 #[allow(unused)]
@@ -126,3 +352,25 @@ pub const FEE_TOKEN_ADDRESS: &str =
     "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
 
 pub const CONFIG_UPDATE_INTERVAL: u64 = 3 * 3600;
+/// Interval (in seconds) at which the config file is checked for changes.
+pub const CONFIG_FILE_CHECK_INTERVAL: u64 = 5;
+
+// Default alerting thresholds, see `config::Thresholds`
+pub const DEFAULT_PRICE_DEVIATION_THRESHOLD: f64 = 0.05;
+pub const DEFAULT_SOURCE_DEVIATION_THRESHOLD: f64 = 0.05;
+pub const DEFAULT_ON_OFF_DEVIATION_THRESHOLD: f64 = 0.025;
+pub const DEFAULT_TIME_SINCE_LAST_UPDATE_THRESHOLD: u64 = 1800;
+pub const DEFAULT_NUM_SOURCES_THRESHOLD: u32 = 5;
+
+/// Names of the spot reference providers that can be configured.
+pub const REFERENCE_PROVIDERS: [&str; 2] = ["defillama", "coingecko"];
+/// Default order in which the reference providers are queried.
+pub const DEFAULT_REFERENCE_PROVIDERS: &str = "defillama,coingecko";
+/// Default minimum number of references that must agree in consensus mode.
+pub const DEFAULT_REFERENCE_MIN_AGREEING: usize = 2;
+/// Default maximum deviation from the median for a reference to agree in consensus mode.
+pub const DEFAULT_REFERENCE_MAX_DEVIATION: f64 = 0.01;
+/// Default duration (in seconds) of the timestamp buckets sharing a cached reference price.
+pub const DEFAULT_REFERENCE_CACHE_BUCKET: u64 = 60;
+/// Default duration (in seconds) after which a cached reference price is evicted.
+pub const DEFAULT_REFERENCE_CACHE_TTL: u64 = 300;
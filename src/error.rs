@@ -4,7 +4,6 @@ use starknet::providers::ProviderError;
 
 #[derive(Debug)]
 pub enum MonitoringError {
-    Price(String),
     Database(diesel::result::Error),
     Connection(String),
     Api(String),
@@ -12,6 +11,9 @@ pub enum MonitoringError {
     OnChain(String),
     Provider(ProviderError),
     InvalidTimestamp(u64),
+    NoConsensus { agreeing: usize, spread: f64 },
+    MissingReferenceId(String),
+    InvalidTableName(String),
 }
 
 impl StdError for MonitoringError {}
@@ -19,7 +21,6 @@ impl StdError for MonitoringError {}
 impl fmt::Display for MonitoringError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            MonitoringError::Price(e) => write!(f, "Price Error: {}", e),
             MonitoringError::Database(e) => write!(f, "Database Error: {}", e),
             MonitoringError::Connection(e) => write!(f, "Connection Error: {}", e),
             MonitoringError::Api(e) => write!(f, "API Error: {}", e),
@@ -27,6 +28,13 @@ impl fmt::Display for MonitoringError {
             MonitoringError::OnChain(e) => write!(f, "OnChain Error: {}", e),
             MonitoringError::Provider(e) => write!(f, "Provider Error: {}", e),
             MonitoringError::InvalidTimestamp(e) => write!(f, "Invalid Timestamp: {}", e),
+            MonitoringError::NoConsensus { agreeing, spread } => write!(
+                f,
+                "No Consensus: only {} references agree (spread {})",
+                agreeing, spread
+            ),
+            MonitoringError::MissingReferenceId(e) => write!(f, "Missing Reference Id: {}", e),
+            MonitoringError::InvalidTableName(e) => write!(f, "Invalid Table Name: {}", e),
         }
     }
 }
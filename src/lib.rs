@@ -1,6 +1,8 @@
 pub(crate) mod config;
 pub(crate) mod constants;
 pub mod models;
+pub(crate) mod registry;
 pub mod schema;
 pub mod types;
 pub(crate) mod utils;
+pub(crate) mod validation;
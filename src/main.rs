@@ -1,19 +1,43 @@
 extern crate diesel;
 extern crate dotenv;
 
-use config::{get_config, periodic_config_update, DataType};
+use clap::Parser;
+use cli::{Cli, Command};
+use config::{
+    get_config, init_config, watch_config_updates, Check, Config, Configs, DataType, NetworkName,
+};
 use diesel_async::pooled_connection::deadpool::*;
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
 use diesel_async::AsyncPgConnection;
 
 use dotenv::dotenv;
 use std::env;
+use std::sync::Arc;
 use std::time::Duration;
 use std::vec;
+use tokio::task::JoinHandle;
 use tokio::time::interval;
 
-use crate::processing::common::{check_publisher_balance, is_syncing};
-
+use crate::constants::{MONITOR_RUNNING_UNSYNCED, MONITOR_SKIPPED_TICKS};
+use crate::error::MonitoringError;
+use crate::models::{FutureEntry, SpotEntry};
+use crate::processing::common::{
+    check_publisher_balance, export_thresholds, prefetch_reference_prices,
+};
+use crate::processing::entry::IndexedEntry;
+use crate::processing::indexer::{
+    check_table_status, retain_table_statuses, table_status, tick_decision, TickDecision,
+};
+use crate::processing::stale_metrics::{
+    remove_network_metrics, remove_stale_api_metrics, remove_stale_checkpoint_metrics,
+    remove_stale_indexer_metrics, remove_stale_pair_metrics, remove_stale_publisher_metrics,
+    remove_stale_vrf_metrics,
+};
+use crate::reference::PrefetchedPrices;
+use crate::snapshot::{Snapshot, SnapshotFormat};
+
+// Command line interface
+mod cli;
 // Configuration
 mod config;
 // Error handling
@@ -24,8 +48,16 @@ mod models;
 mod monitoring;
 // Processing functions
 mod processing;
+// Reference price providers
+mod reference;
+// On-chain registry changes
+mod registry;
+// Alerting rules generation
+mod rules;
 // Server
 mod server;
+// One-shot run of the checks
+mod snapshot;
 // Database schema
 mod schema;
 // Constants
@@ -34,6 +66,8 @@ mod constants;
 mod types;
 // Utils
 mod utils;
+// Config validation
+mod validation;
 
 #[cfg(test)]
 mod tests;
@@ -45,239 +79,583 @@ async fn main() {
     // Load environment variables from .env file
     dotenv().ok();
 
-    // Define the pairs to monitor
-    let monitoring_config = get_config(None).await;
+    match Cli::parse().command.unwrap_or_default() {
+        Command::Serve => serve().await,
+        Command::CheckConfig { allow_warnings } => {
+            std::process::exit(cli::check_config(allow_warnings).await)
+        }
+        Command::Snapshot { format } => std::process::exit(snapshot(format).await),
+        Command::GenerateRules { output } => std::process::exit(cli::generate_rules(output)),
+    }
+}
+
+/// Monitors the configured networks and serves the metrics.
+async fn serve() {
+    // Define the pairs to monitor, exiting when the config cannot be used
+    let monitoring_config = match Configs::create_from_env().await {
+        Ok(configs) => configs,
+        Err(report) => {
+            log::error!("{report}");
+            std::process::exit(1);
+        }
+    };
+    if !monitoring_config.report().is_empty() {
+        log::warn!("{}", monitoring_config.report());
+    }
+    let monitoring_config = init_config(monitoring_config).await;
 
     log::info!("Successfully fetched config: {:?}", monitoring_config);
 
     tokio::spawn(server::run_metrics_server());
 
-    let database_url: String = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let config = AsyncDieselConnectionManager::<diesel_async::AsyncPgConnection>::new(database_url);
-    let pool = Pool::builder(config).build().unwrap();
-    // Monitor spot/future in parallel
-    let spot_monitoring = tokio::spawn(monitor(pool.clone(), true, &DataType::Spot));
-    let future_monitoring = tokio::spawn(monitor(pool.clone(), true, &DataType::Future));
-
-    let publisher_monitoring = tokio::spawn(publisher_monitor(pool.clone(), false));
-
-    let api_monitoring = tokio::spawn(monitor_api());
-
-    let config_update = tokio::spawn(periodic_config_update());
+    let pool = database_pool();
+
+    // Monitor spot/future/publishers/API of each network in parallel
+    let mut tasks = Vec::new();
+    for network_config in monitoring_config.networks() {
+        let network = &network_config.network().name;
+        tasks.push((
+            format!("[{network}][INDEXER] Monitoring"),
+            tokio::spawn(indexer_monitor(pool.clone(), network.clone())),
+        ));
+        tasks.push((
+            format!("[{network}][SPOT] Monitoring"),
+            tokio::spawn(monitor(
+                pool.clone(),
+                network.clone(),
+                true,
+                &DataType::Spot,
+            )),
+        ));
+        tasks.push((
+            format!("[{network}][FUTURE] Monitoring"),
+            tokio::spawn(monitor(
+                pool.clone(),
+                network.clone(),
+                true,
+                &DataType::Future,
+            )),
+        ));
+        if network_config.network().api_url.is_some() {
+            tasks.push((
+                format!("[{network}][API] Monitoring"),
+                tokio::spawn(monitor_api(network.clone())),
+            ));
+        }
+        tasks.push((
+            format!("[{network}][PUBLISHERS] Monitoring"),
+            tokio::spawn(publisher_monitor(pool.clone(), network.clone(), false)),
+        ));
+        tasks.push((
+            format!("[{network}][CHECKPOINTS] Monitoring"),
+            tokio::spawn(checkpoint_monitor(pool.clone(), network.clone())),
+        ));
+        if network_config.network().vrf_table.is_some() {
+            tasks.push((
+                format!("[{network}][VRF] Monitoring"),
+                tokio::spawn(vrf_monitor(pool.clone(), network.clone())),
+            ));
+        }
+    }
+    tasks.push((
+        "[CONFIG] Config Update".to_string(),
+        tokio::spawn(watch_config_updates()),
+    ));
 
     // Wait for the monitoring to finish
-    let results = futures::future::join_all(vec![
-        spot_monitoring,
-        future_monitoring,
-        api_monitoring,
-        publisher_monitoring,
-        config_update,
-    ])
-    .await;
+    let (names, handles): (Vec<_>, Vec<_>) = tasks.into_iter().unzip();
+    let results = futures::future::join_all(handles).await;
 
     // Check if any of the monitoring tasks failed
-    if let Err(e) = &results[0] {
-        log::error!("[SPOT] Monitoring failed: {:?}", e);
+    for (name, result) in names.iter().zip(results) {
+        if let Err(e) = result {
+            log::error!("{name} failed: {:?}", e);
+        }
     }
-    if let Err(e) = &results[1] {
-        log::error!("[FUTURE] Monitoring failed: {:?}", e);
+}
+
+/// Returns the current config of the given network, `None` if it is no longer configured.
+async fn network_config(network: &NetworkName) -> Option<Arc<Config>> {
+    let config = get_config(None).await.network(network).cloned();
+    if config.is_none() {
+        log::warn!("[{network}] Network is no longer configured, skipping");
     }
-    if let Err(e) = &results[2] {
-        log::error!("[API] Monitoring failed: {:?}", e);
+    config
+}
+
+fn database_pool() -> Pool<AsyncPgConnection> {
+    let database_url: String = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let config = AsyncDieselConnectionManager::<diesel_async::AsyncPgConnection>::new(database_url);
+    Pool::builder(config).build().unwrap()
+}
+
+/// Runs every check of each network once, then prints the computed values.
+/// Returns the exit code of the process, non-zero when the config is invalid or a check failed.
+async fn snapshot(format: SnapshotFormat) -> i32 {
+    let monitoring_config = match Configs::create_from_env().await {
+        Ok(configs) => init_config(configs).await,
+        Err(report) => {
+            eprintln!("{report}");
+            return 1;
+        }
+    };
+
+    let pool = database_pool();
+
+    let mut errors = Vec::new();
+    for config in monitoring_config.networks() {
+        // Check the indexers first so the data checks use their status
+        errors.extend(indexer_monitor_tick(&pool, config).await);
+        let (spot, future, publishers, api, checkpoints, vrf) = tokio::join!(
+            monitor_tick(&pool, config, false, &DataType::Spot),
+            monitor_tick(&pool, config, false, &DataType::Future),
+            publisher_monitor_tick(&pool, config, false),
+            monitor_api_tick(config),
+            checkpoint_monitor_tick(&pool, config),
+            vrf_monitor_tick(&pool, config),
+        );
+        errors.extend([spot, future, publishers, api, checkpoints, vrf].concat());
     }
-    if let Err(e) = &results[3] {
-        log::error!("[PUBLISHERS] Monitoring failed: {:?}", e);
+
+    let snapshot = Snapshot::new(&prometheus::gather(), errors);
+    println!("{}", snapshot.render(format));
+
+    match snapshot.errors.is_empty() {
+        true => 0,
+        false => 1,
     }
+}
 
-    if let Err(e) = &results[4] {
-        log::error!("[CONFIG] Config Update failed: {:?}", e);
+/// Waits for the tasks and logs their results.
+/// Returns the errors of the failed tasks, prefixed by the given tag.
+async fn join_tasks<T>(
+    tag: &str,
+    tasks: Vec<JoinHandle<Result<T, MonitoringError>>>,
+) -> Vec<String> {
+    let results: Vec<_> = futures::future::join_all(tasks).await;
+
+    // Process or output the results
+    let mut errors = Vec::new();
+    for result in &results {
+        match result {
+            Ok(data) => match data {
+                Ok(_) => log::info!("{tag} Task finished successfully",),
+                Err(e) => {
+                    log::error!("{tag} Task failed with error: {e}");
+                    errors.push(format!("{tag} {e}"));
+                }
+            },
+            Err(e) => {
+                log::error!("{tag} Task failed with error: {:?}", e);
+                errors.push(format!("{tag} {e}"));
+            }
+        }
     }
+    errors
 }
 
-pub(crate) async fn monitor_api() {
-    let monitoring_config = get_config(None).await;
-    log::info!("[API] Monitoring API..");
+pub(crate) async fn monitor_api(network: NetworkName) {
+    let intervals = get_config(None).await.intervals().clone();
+    log::info!("[{network}][API] Monitoring API..");
 
-    let mut interval = interval(Duration::from_secs(30));
+    let mut interval = interval(Duration::from_secs(intervals.api));
 
     loop {
         interval.tick().await; // Wait for the next tick
 
-        let mut tasks: Vec<_> = monitoring_config
-            .sources(DataType::Spot)
-            .iter()
-            .flat_map(|(pair, _sources)| {
-                vec![tokio::spawn(Box::pin(
-                    processing::api::process_data_by_pair(pair.clone()),
-                ))]
-            })
-            .collect();
-        tasks.push(tokio::spawn(Box::pin(
-            processing::api::process_sequencer_data(),
-        )));
+        // Use the latest config, which may have been reloaded
+        let Some(monitoring_config) = network_config(&network).await else {
+            remove_network_metrics(network.as_str());
+            continue;
+        };
+        monitor_api_tick(&monitoring_config).await;
+    }
+}
 
-        let results: Vec<_> = futures::future::join_all(tasks).await;
+/// Checks the API and sequencer prices of the network once.
+async fn monitor_api_tick(monitoring_config: &Arc<Config>) -> Vec<String> {
+    let network = &monitoring_config.network().name;
 
-        // Process or output the results
-        for result in &results {
-            match result {
-                Ok(data) => match data {
-                    Ok(_) => log::info!("[API] Task finished successfully",),
-                    Err(e) => log::error!("[API] Task failed with error: {e}"),
-                },
-                Err(e) => log::error!("[API] Task failed with error: {:?}", e),
-            }
-        }
+    remove_stale_api_metrics(monitoring_config);
+    if monitoring_config.network().api_url.is_none() {
+        return Vec::new();
     }
+
+    let pairs: Vec<String> = monitoring_config
+        .sources(DataType::Spot)
+        .keys()
+        .filter(|pair| monitoring_config.is_check_enabled(DataType::Spot, pair, Check::Api))
+        .cloned()
+        .collect();
+    prefetch_reference_prices(&pairs, true).await;
+
+    let mut tasks: Vec<_> = pairs
+        .iter()
+        .flat_map(|pair| {
+            vec![tokio::spawn(Box::pin(
+                processing::api::process_data_by_pair(monitoring_config.clone(), pair.clone()),
+            ))]
+        })
+        .collect();
+    if monitoring_config.network().sequencer_url.is_some() {
+        tasks.push(tokio::spawn(Box::pin(
+            processing::api::process_sequencer_data(monitoring_config.clone()),
+        )));
+    }
+
+    join_tasks(&format!("[{network}][API]"), tasks).await
 }
 
 pub(crate) async fn monitor(
     pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    network: NetworkName,
     wait_for_syncing: bool,
     data_type: &DataType,
 ) {
-    let monitoring_config = get_config(None).await;
+    let intervals = get_config(None).await.intervals().clone();
 
-    let mut interval = interval(Duration::from_secs(30));
+    let mut interval = interval(Duration::from_secs(intervals.monitor));
 
     loop {
         interval.tick().await; // Wait for the next tick
 
-        // Skip if indexer is still syncing
-        if wait_for_syncing {
-            match is_syncing(data_type).await {
-                Ok(true) => {
-                    log::info!("[{data_type}] Indexers are still syncing ♻️");
-                    continue;
-                }
-                Ok(false) => {
-                    log::info!("[{data_type}] Indexers are synced ✅");
-                }
-                Err(e) => {
-                    log::error!(
-                        "[{data_type}] Failed to check if indexers are syncing: {:?}",
-                        e
-                    );
-                    continue;
-                }
-            }
+        // Use the latest config, which may have been reloaded
+        let Some(monitoring_config) = network_config(&network).await else {
+            remove_network_metrics(network.as_str());
+            continue;
+        };
+        monitor_tick(&pool, &monitoring_config, wait_for_syncing, data_type).await;
+    }
+}
+
+/// Checks the pairs of the given type of data of the network once.
+async fn monitor_tick(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    monitoring_config: &Arc<Config>,
+    wait_for_syncing: bool,
+    data_type: &DataType,
+) -> Vec<String> {
+    let network = &monitoring_config.network().name;
+
+    // Stop exporting the pairs and sources removed from the config
+    remove_stale_pair_metrics(monitoring_config, data_type);
+    export_thresholds(monitoring_config, data_type);
+
+    // Skip if indexer is still syncing, depending on the syncing policy
+    if wait_for_syncing {
+        let check = data_type.to_string().to_ascii_lowercase();
+        let table_name = monitoring_config.table_name(data_type.clone());
+        if let Err(errors) = check_syncing(pool, monitoring_config, &check, table_name).await {
+            return errors;
         }
+    }
 
-        let tasks: Vec<_> = monitoring_config
-            .sources(data_type.clone())
-            .iter()
-            .flat_map(|(pair, sources)| match data_type {
-                DataType::Spot => {
-                    vec![
-                        tokio::spawn(Box::pin(processing::spot::process_data_by_pair(
-                            pool.clone(),
-                            pair.clone(),
-                        ))),
-                        tokio::spawn(Box::pin(
-                            processing::spot::process_data_by_pair_and_sources(
-                                pool.clone(),
-                                pair.clone(),
-                                sources.to_vec(),
-                            ),
-                        )),
-                    ]
-                }
-                DataType::Future => {
-                    vec![
-                        tokio::spawn(Box::pin(processing::future::process_data_by_pair(
-                            pool.clone(),
-                            pair.clone(),
-                        ))),
-                        tokio::spawn(Box::pin(
-                            processing::future::process_data_by_pair_and_sources(
-                                pool.clone(),
-                                pair.clone(),
-                                sources.to_vec(),
-                            ),
-                        )),
-                    ]
-                }
-            })
-            .collect();
-
-        let results: Vec<_> = futures::future::join_all(tasks).await;
-
-        // Process or output the results
-        for result in &results {
-            match result {
-                Ok(data) => match data {
-                    Ok(_) => log::info!("[{data_type}] Task finished successfully",),
-                    Err(e) => log::error!("[{data_type}] Task failed with error: {e}"),
-                },
-                Err(e) => log::error!("[{data_type}] Task failed with error: {:?}", e),
+    // The spot references are fetched in batch, the future ones per pair
+    let references = match data_type {
+        DataType::Spot => {
+            let pairs: Vec<String> = monitoring_config
+                .sources(DataType::Spot)
+                .keys()
+                .cloned()
+                .collect();
+            Arc::new(prefetch_reference_prices(&pairs, false).await)
+        }
+        DataType::Future => Arc::default(),
+    };
+
+    let tasks: Vec<_> = monitoring_config
+        .sources(data_type.clone())
+        .iter()
+        .flat_map(|(pair, sources)| match data_type {
+            DataType::Spot => {
+                pair_tasks::<SpotEntry>(pool, monitoring_config, pair, sources, &references)
+            }
+            DataType::Future => {
+                pair_tasks::<FutureEntry>(pool, monitoring_config, pair, sources, &references)
             }
+        })
+        .collect();
+
+    join_tasks(&format!("[{network}][{data_type}]"), tasks).await
+}
+
+/// Applies the syncing policy of the network to a tick of the given check,
+/// based on the status of the indexer of the table it reads.
+/// Returns the errors to report when the tick is skipped.
+async fn check_syncing(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    monitoring_config: &Arc<Config>,
+    check: &str,
+    table_name: &str,
+) -> Result<(), Vec<String>> {
+    let network = &monitoring_config.network().name;
+    let tag = format!("[{network}][{}]", check.to_ascii_uppercase());
+
+    let status = table_status(pool, monitoring_config, table_name).await;
+    if let Err(e) = &status {
+        log::error!("{tag} Failed to check if indexers are syncing: {:?}", e);
+    }
+
+    let decision = tick_decision(monitoring_config.syncing_policy(), status.as_ref().ok());
+    MONITOR_RUNNING_UNSYNCED
+        .with_label_values(&[network.as_str(), check])
+        .set((decision == TickDecision::RunUnsynced) as i64);
+
+    match decision {
+        TickDecision::Run => {
+            log::info!("{tag} Indexers are synced ✅");
+            Ok(())
+        }
+        TickDecision::RunUnsynced => {
+            log::warn!("{tag} Indexers are not synced, running anyway ⚠️");
+            Ok(())
+        }
+        TickDecision::Skip(reason) => {
+            log::info!("{tag} Indexers are still syncing ♻️ ({reason}), skipping");
+            MONITOR_SKIPPED_TICKS
+                .with_label_values(&[network.as_str(), check, reason])
+                .inc();
+            Err(status
+                .err()
+                .map(|e| vec![format!("{tag} {e}")])
+                .unwrap_or_default())
         }
     }
 }
 
+/// Spawns the monitoring tasks of a pair.
+fn pair_tasks<T: IndexedEntry>(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &Arc<Config>,
+    pair: &str,
+    sources: &[String],
+    references: &Arc<PrefetchedPrices>,
+) -> Vec<JoinHandle<Result<u64, MonitoringError>>> {
+    vec![
+        tokio::spawn(Box::pin(processing::entry::process_data_by_pair::<T>(
+            pool.clone(),
+            config.clone(),
+            pair.to_string(),
+            references.clone(),
+        ))),
+        tokio::spawn(Box::pin(
+            processing::entry::process_data_by_pair_and_sources::<T>(
+                pool.clone(),
+                config.clone(),
+                pair.to_string(),
+                sources.to_vec(),
+                references.clone(),
+            ),
+        )),
+    ]
+}
+
 pub(crate) async fn publisher_monitor(
     pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    network: NetworkName,
     wait_for_syncing: bool,
 ) {
-    log::info!("[PUBLISHERS] Monitoring Publishers..");
+    log::info!("[{network}][PUBLISHERS] Monitoring Publishers..");
 
-    let mut interval = interval(Duration::from_secs(30));
-    let monitoring_config: arc_swap::Guard<std::sync::Arc<config::Config>> = get_config(None).await;
+    let intervals = get_config(None).await.intervals().clone();
+    let mut interval = interval(Duration::from_secs(intervals.publishers));
 
     loop {
         interval.tick().await; // Wait for the next tick
 
-        if wait_for_syncing {
-            match is_syncing(&DataType::Spot).await {
-                Ok(true) => {
-                    log::info!("[PUBLISHERS] Indexers are still syncing ♻️");
-                    continue;
-                }
-                Ok(false) => {
-                    log::info!("PUBLISHERS] Indexers are synced ✅");
-                }
-                Err(e) => {
-                    log::error!(
-                        "[PUBLISHERS] Failed to check if indexers are syncing: {:?}",
-                        e
-                    );
-                    continue;
-                }
-            }
-        }
+        // Use the latest config, which may have been reloaded
+        let Some(monitoring_config) = network_config(&network).await else {
+            remove_network_metrics(network.as_str());
+            continue;
+        };
+        publisher_monitor_tick(&pool, &monitoring_config, wait_for_syncing).await;
+    }
+}
 
-        let tasks: Vec<_> = monitoring_config
-            .all_publishers()
-            .iter()
-            .flat_map(|(publisher, address)| {
-                vec![
-                    tokio::spawn(Box::pin(check_publisher_balance(
-                        publisher.clone(),
-                        *address,
-                    ))),
-                    tokio::spawn(Box::pin(processing::spot::process_data_by_publisher(
-                        pool.clone(),
-                        publisher.clone(),
-                    ))),
-                    tokio::spawn(Box::pin(processing::future::process_data_by_publisher(
-                        pool.clone(),
-                        publisher.clone(),
-                    ))),
-                ]
-            })
-            .collect();
-
-        let results: Vec<_> = futures::future::join_all(tasks).await;
-
-        // Process or output the results
-        for result in &results {
-            match result {
-                Ok(data) => match data {
-                    Ok(_) => log::info!("[PUBLISHERS]: Task finished successfully",),
-                    Err(e) => log::error!("[PUBLISHERS]: Task failed with error: {e}"),
-                },
-                Err(e) => log::error!("[PUBLISHERS]: Task failed with error: {:?}", e),
-            }
+/// Checks the balance and last updates of the publishers of the network once.
+async fn publisher_monitor_tick(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    monitoring_config: &Arc<Config>,
+    wait_for_syncing: bool,
+) -> Vec<String> {
+    let network = &monitoring_config.network().name;
+
+    // Stop exporting the publishers removed from the config
+    remove_stale_publisher_metrics(monitoring_config);
+
+    if wait_for_syncing {
+        let table_name = monitoring_config.table_name(DataType::Spot);
+        if let Err(errors) = check_syncing(pool, monitoring_config, "publishers", table_name).await
+        {
+            return errors;
         }
     }
+
+    let tasks: Vec<_> = monitoring_config
+        .all_publishers()
+        .iter()
+        .flat_map(|(publisher, address)| {
+            vec![
+                tokio::spawn(Box::pin(check_publisher_balance(
+                    monitoring_config.clone(),
+                    publisher.clone(),
+                    *address,
+                ))),
+                tokio::spawn(Box::pin(processing::entry::process_data_by_publisher::<
+                    SpotEntry,
+                >(
+                    pool.clone(),
+                    monitoring_config.clone(),
+                    publisher.clone(),
+                ))),
+                tokio::spawn(Box::pin(processing::entry::process_data_by_publisher::<
+                    FutureEntry,
+                >(
+                    pool.clone(),
+                    monitoring_config.clone(),
+                    publisher.clone(),
+                ))),
+            ]
+        })
+        .collect();
+
+    join_tasks(&format!("[{network}][PUBLISHERS]"), tasks).await
+}
+
+pub(crate) async fn checkpoint_monitor(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    network: NetworkName,
+) {
+    log::info!("[{network}][CHECKPOINTS] Monitoring Checkpoints..");
+
+    let intervals = get_config(None).await.intervals().clone();
+    let mut interval = interval(Duration::from_secs(intervals.checkpoints));
+
+    loop {
+        interval.tick().await; // Wait for the next tick
+
+        // Use the latest config, which may have been reloaded
+        let Some(monitoring_config) = network_config(&network).await else {
+            remove_network_metrics(network.as_str());
+            continue;
+        };
+        checkpoint_monitor_tick(&pool, &monitoring_config).await;
+    }
+}
+
+/// Checks the last checkpoint of the spot pairs of the network once.
+async fn checkpoint_monitor_tick(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    monitoring_config: &Arc<Config>,
+) -> Vec<String> {
+    let network = &monitoring_config.network().name;
+
+    // Stop exporting the pairs removed from the config
+    remove_stale_checkpoint_metrics(monitoring_config);
+
+    let tasks: Vec<_> = monitoring_config
+        .sources(DataType::Spot)
+        .keys()
+        .filter(|pair| monitoring_config.is_check_enabled(DataType::Spot, pair, Check::Checkpoint))
+        .map(|pair| {
+            tokio::spawn(Box::pin(
+                processing::checkpoint::process_checkpoint_by_pair(
+                    pool.clone(),
+                    monitoring_config.clone(),
+                    pair.clone(),
+                ),
+            ))
+        })
+        .collect();
+
+    join_tasks(&format!("[{network}][CHECKPOINTS]"), tasks).await
+}
+
+pub(crate) async fn vrf_monitor(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    network: NetworkName,
+) {
+    log::info!("[{network}][VRF] Monitoring VRF requests..");
+
+    let intervals = get_config(None).await.intervals().clone();
+    let mut interval = interval(Duration::from_secs(intervals.vrf));
+
+    loop {
+        interval.tick().await; // Wait for the next tick
+
+        // Use the latest config, which may have been reloaded
+        let Some(monitoring_config) = network_config(&network).await else {
+            remove_network_metrics(network.as_str());
+            continue;
+        };
+        vrf_monitor_tick(&pool, &monitoring_config).await;
+    }
+}
+
+/// Checks the VRF requests of the network once.
+async fn vrf_monitor_tick(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    monitoring_config: &Arc<Config>,
+) -> Vec<String> {
+    let network = &monitoring_config.network().name;
+
+    remove_stale_vrf_metrics(monitoring_config);
+    if monitoring_config.network().vrf_table.is_none() {
+        return Vec::new();
+    }
+
+    let tasks = vec![tokio::spawn(Box::pin(
+        processing::vrf::process_vrf_requests(pool.clone(), monitoring_config.clone()),
+    ))];
+
+    join_tasks(&format!("[{network}][VRF]"), tasks).await
+}
+
+pub(crate) async fn indexer_monitor(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    network: NetworkName,
+) {
+    log::info!("[{network}][INDEXER] Monitoring Indexers..");
+
+    let intervals = get_config(None).await.intervals().clone();
+    let mut interval = interval(Duration::from_secs(intervals.indexer));
+
+    loop {
+        interval.tick().await; // Wait for the next tick
+
+        // Use the latest config, which may have been reloaded
+        let Some(monitoring_config) = network_config(&network).await else {
+            remove_network_metrics(network.as_str());
+            retain_table_statuses(network.as_str(), &[]);
+            continue;
+        };
+        indexer_monitor_tick(&pool, &monitoring_config).await;
+    }
+}
+
+/// Checks the indexing status of every table read on the network once.
+async fn indexer_monitor_tick(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    monitoring_config: &Arc<Config>,
+) -> Vec<String> {
+    let network = &monitoring_config.network().name;
+
+    // Stop exporting the tables that are no longer read
+    remove_stale_indexer_metrics(monitoring_config);
+    let tables = monitoring_config.indexed_tables();
+    retain_table_statuses(network.as_str(), &tables);
+
+    let tasks: Vec<_> = tables
+        .into_iter()
+        .map(|table| {
+            let pool = pool.clone();
+            let config = monitoring_config.clone();
+            let table = table.to_string();
+            tokio::spawn(Box::pin(async move {
+                check_table_status(&pool, &config, &table).await
+            }))
+        })
+        .collect();
+
+    join_tasks(&format!("[{network}][INDEXER]"), tasks).await
 }
@@ -89,3 +89,23 @@ pub struct VrfRequest {
     pub _cursor: (Bound<i64>, Bound<i64>),
     pub data_id: String,
 }
+
+/// Number of current VRF requests of a requestor with a given status.
+#[derive(Debug, QueryableByName)]
+pub struct VrfRequestCount {
+    #[diesel(sql_type = diesel::sql_types::Varchar)]
+    pub requestor_address: String,
+    #[diesel(sql_type = diesel::sql_types::Numeric)]
+    pub status: BigDecimal,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub count: i64,
+}
+
+/// Latest block indexed in a table, read from the table itself.
+#[derive(Debug, QueryableByName)]
+pub struct IndexedBlock {
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::BigInt>)]
+    pub block_number: Option<i64>,
+    #[diesel(sql_type = diesel::sql_types::Timestamp)]
+    pub block_timestamp: NaiveDateTime,
+}
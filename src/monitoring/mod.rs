@@ -1,9 +1,14 @@
+pub mod normalize_price;
+pub mod on_chain_median;
 pub mod on_off_deviation;
 pub mod price_deviation;
 pub mod publisher_balance;
 pub mod source_deviation;
 pub mod time_since_last_update;
+pub mod vrf;
 
+pub use normalize_price::normalize_price;
+pub use on_chain_median::on_chain_median;
 pub use on_off_deviation::on_off_price_deviation;
 pub use price_deviation::price_deviation;
 pub use publisher_balance::publisher_balance;
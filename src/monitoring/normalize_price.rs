@@ -0,0 +1,19 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use num_bigint::BigInt;
+
+use crate::error::MonitoringError;
+
+/// Normalizes a raw price with the given number of decimals,
+/// e.g 4222012345678 with 8 decimals is 42220.12345678.
+/// The division is exact, only the final conversion to f64 is rounded.
+pub fn normalize_price(price: &BigDecimal, decimals: u32) -> Result<f64, MonitoringError> {
+    let scale = BigDecimal::new(BigInt::from(1), decimals as i64);
+
+    (price * scale)
+        .to_f64()
+        .filter(|price| price.is_finite())
+        .ok_or(MonitoringError::Conversion(format!(
+            "Failed to normalize price {} with {} decimals",
+            price, decimals
+        )))
+}
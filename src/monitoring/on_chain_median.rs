@@ -0,0 +1,89 @@
+use bigdecimal::BigDecimal;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, Felt, FunctionCall},
+        utils::cairo_short_string_to_felt,
+    },
+    macros::selector,
+    providers::Provider,
+};
+
+use crate::{
+    config::{Config, DataType},
+    error::MonitoringError,
+    monitoring::normalize_price,
+    utils::try_felt_to_u32,
+};
+
+/// Median price aggregated by the oracle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnChainMedian {
+    /// Median price normalized to the pair decimals.
+    pub price: f64,
+    pub num_sources_aggregated: u32,
+}
+
+/// Reads the median price of a pair from the oracle `get_data_median`.
+///
+/// # Arguments
+///
+/// * `config` - The config of the network.
+/// * `pair_id` - The pair id.
+/// * `data_type` - The type of data to get, futures are read from the perpetual contract.
+///
+/// # Returns
+///
+/// * `Ok(OnChainMedian)` - The median price and the number of sources aggregated.
+/// * `Err(MonitoringError)` - The error.
+pub async fn on_chain_median(
+    config: &Config,
+    pair_id: &str,
+    data_type: DataType,
+) -> Result<OnChainMedian, MonitoringError> {
+    let client = &config.network().provider;
+    let field_pair = cairo_short_string_to_felt(pair_id)
+        .map_err(|e| MonitoringError::Conversion(format!("Failed to convert pair id {:?}", e)))?;
+
+    let calldata = match data_type {
+        DataType::Spot => vec![Felt::ZERO, field_pair],
+        DataType::Future => vec![Felt::ONE, field_pair, Felt::ZERO],
+    };
+
+    let data = client
+        .call(
+            FunctionCall {
+                contract_address: config.network().oracle_address,
+                entry_point_selector: selector!("get_data_median"),
+                calldata,
+            },
+            BlockId::Tag(BlockTag::Latest),
+        )
+        .await
+        .map_err(|e| MonitoringError::OnChain(e.to_string()))?;
+
+    let decimals = config
+        .decimals(data_type)
+        .get(pair_id)
+        .ok_or(MonitoringError::OnChain(format!(
+            "Failed to get decimals for pair {:?}",
+            pair_id
+        )))?;
+
+    let raw_price = data
+        .first()
+        .ok_or(MonitoringError::OnChain("No data".to_string()))?
+        .to_bigint();
+    let price = normalize_price(&BigDecimal::from(raw_price), *decimals)?;
+
+    let num_sources = data
+        .get(3)
+        .ok_or(MonitoringError::OnChain("No num sources".to_string()))?;
+    let num_sources_aggregated = try_felt_to_u32(num_sources).map_err(|e| {
+        MonitoringError::Conversion(format!("Failed to convert num sources {:?}", e))
+    })?;
+
+    Ok(OnChainMedian {
+        price,
+        num_sources_aggregated,
+    })
+}
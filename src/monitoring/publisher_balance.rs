@@ -1,4 +1,4 @@
-use bigdecimal::ToPrimitive;
+use bigdecimal::BigDecimal;
 use starknet::{
     core::types::{BlockId, BlockTag, Felt, FunctionCall},
     macros::selector,
@@ -6,13 +6,14 @@ use starknet::{
 };
 
 use crate::constants::{FEE_TOKEN_ADDRESS, FEE_TOKEN_DECIMALS};
-use crate::{config::get_config, error::MonitoringError};
+use crate::{config::Config, error::MonitoringError, monitoring::normalize_price};
 
 /// Returns the balance of a given publisher address
 /// Note: Currently only reads ETH balance
-pub async fn publisher_balance(publisher_address: Felt) -> Result<f64, MonitoringError> {
-    let config = get_config(None).await;
-
+pub async fn publisher_balance(
+    config: &Config,
+    publisher_address: Felt,
+) -> Result<f64, MonitoringError> {
     let client = &config.network().provider;
     let token_balance = client
         .call(
@@ -26,15 +27,11 @@ pub async fn publisher_balance(publisher_address: Felt) -> Result<f64, Monitorin
         .await
         .map_err(|e| MonitoringError::OnChain(e.to_string()))?;
 
-    let on_chain_balance = token_balance
+    let raw_balance = token_balance
         .first()
         .ok_or(MonitoringError::OnChain("No data".to_string()))?
-        .to_bigint()
-        .to_f64()
-        .ok_or(MonitoringError::Conversion(
-            "Failed to convert to f64".to_string(),
-        ))?
-        / 10_f64.powi(FEE_TOKEN_DECIMALS as i32);
+        .to_bigint();
+    let on_chain_balance = normalize_price(&BigDecimal::from(raw_balance), FEE_TOKEN_DECIMALS)?;
 
     Ok(on_chain_balance)
 }
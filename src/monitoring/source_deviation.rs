@@ -1,61 +1,15 @@
-use bigdecimal::ToPrimitive;
-use starknet::{
-    core::{
-        types::{BlockId, BlockTag, Felt, FunctionCall},
-        utils::cairo_short_string_to_felt,
-    },
-    macros::selector,
-    providers::Provider,
-};
-
-use crate::{config::get_config, error::MonitoringError, types::Entry, utils::try_felt_to_u32};
+use crate::{config::Config, error::MonitoringError, monitoring::on_chain_median, types::Entry};
 
 /// Calculates the deviation from the on-chain price
 /// Returns the deviation and the number of sources aggregated
 pub async fn source_deviation<T: Entry>(
+    config: &Config,
     query: &T,
     normalized_price: f64,
 ) -> Result<(f64, u32), MonitoringError> {
-    let config = get_config(None).await;
-
-    let client = &config.network().provider;
-    let field_pair =
-        cairo_short_string_to_felt(query.pair_id()).expect("failed to convert pair id");
-
-    let data = client
-        .call(
-            FunctionCall {
-                contract_address: config.network().oracle_address,
-                entry_point_selector: selector!("get_data_median"),
-                calldata: vec![Felt::ZERO, field_pair],
-            },
-            BlockId::Tag(BlockTag::Latest),
-        )
-        .await
-        .map_err(|e| MonitoringError::OnChain(e.to_string()))?;
-
-    let decimals = config
-        .decimals(query.data_type())
-        .get(query.pair_id())
-        .ok_or(MonitoringError::OnChain(format!(
-            "Failed to get decimals for pair {:?}",
-            query.pair_id()
-        )))?;
-
-    let on_chain_price = data
-        .first()
-        .ok_or(MonitoringError::OnChain("No data".to_string()))?
-        .to_bigint()
-        .to_f64()
-        .ok_or(MonitoringError::Conversion(
-            "Failed to convert to f64".to_string(),
-        ))?
-        / 10u32.pow(*decimals as u32) as f64;
+    let median = on_chain_median(config, query.pair_id(), T::data_type()).await?;
 
-    let deviation = (normalized_price - on_chain_price) / on_chain_price;
-    let num_sources_aggregated = try_felt_to_u32(data.get(3).unwrap()).map_err(|e| {
-        MonitoringError::Conversion(format!("Failed to convert num sources {:?}", e))
-    })?;
+    let deviation = (normalized_price - median.price) / median.price;
 
-    Ok((deviation, num_sources_aggregated))
+    Ok((deviation, median.num_sources_aggregated))
 }
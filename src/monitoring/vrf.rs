@@ -0,0 +1,128 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::NaiveDateTime;
+use strum::{Display, IntoStaticStr};
+
+use crate::models::VrfRequest;
+
+/// Status of a VRF request, as stored by the randomness contract.
+#[derive(Debug, Display, IntoStaticStr, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[strum(serialize_all = "snake_case")]
+pub enum VrfStatus {
+    Uninitialized = 0,
+    Received = 1,
+    Fulfilled = 2,
+    Cancelled = 3,
+    OutOfGas = 4,
+    Refunded = 5,
+    Unknown,
+}
+
+impl VrfStatus {
+    pub fn from_code(code: &BigDecimal) -> Self {
+        match code.to_u8() {
+            Some(0) => Self::Uninitialized,
+            Some(1) => Self::Received,
+            Some(2) => Self::Fulfilled,
+            Some(3) => Self::Cancelled,
+            Some(4) => Self::OutOfGas,
+            Some(5) => Self::Refunded,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Age buckets of the pending requests, by maximum age (in seconds).
+pub const VRF_AGE_BUCKETS: [(u64, &str); 5] = [
+    (60, "<1m"),
+    (300, "1m-5m"),
+    (900, "5m-15m"),
+    (3600, "15m-1h"),
+    (u64::MAX, ">1h"),
+];
+
+/// Window (in seconds) of the fulfilled VRF requests the latency is computed on.
+pub const VRF_LATENCY_WINDOW: u64 = 3600;
+
+/// Quantiles of the fulfillment latency that are exported.
+pub const VRF_LATENCY_QUANTILES: [f64; 4] = [0.5, 0.9, 0.99, 1.0];
+
+/// Statistics of the VRF requests, see `VrfStats::new`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VrfStats {
+    /// Number of pending requests per age bucket, see `VRF_AGE_BUCKETS`.
+    pub pending_by_age: Vec<(&'static str, i64)>,
+    /// Age of the oldest pending request. (in seconds)
+    pub oldest_pending: u64,
+    /// Number of pending requests whose minimum block number is already past.
+    pub past_minimum_block: i64,
+    /// Fulfillment latencies of the requests fulfilled in the window, sorted. (in seconds)
+    pub latencies: Vec<u64>,
+}
+
+impl VrfStats {
+    /// Computes the statistics of the requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - The pending requests and the ones updated in the window.
+    /// * `now` - The current time.
+    /// * `block_number` - The current block number.
+    /// * `window` - Requests fulfilled for longer than this are ignored. (in seconds)
+    pub fn new(
+        requests: &[VrfRequest],
+        now: NaiveDateTime,
+        block_number: u64,
+        window: u64,
+    ) -> Self {
+        let mut stats = VrfStats {
+            pending_by_age: VRF_AGE_BUCKETS
+                .iter()
+                .map(|(_, bucket)| (*bucket, 0))
+                .collect(),
+            ..Default::default()
+        };
+
+        for request in requests {
+            match VrfStatus::from_code(&request.status) {
+                VrfStatus::Received => {
+                    let age = seconds_between(request.created_at, now);
+                    let bucket = VRF_AGE_BUCKETS
+                        .iter()
+                        .position(|(max_age, _)| age < *max_age)
+                        .unwrap_or(VRF_AGE_BUCKETS.len() - 1);
+                    stats.pending_by_age[bucket].1 += 1;
+                    stats.oldest_pending = stats.oldest_pending.max(age);
+
+                    if request
+                        .minimum_block_number
+                        .to_u64()
+                        .is_some_and(|minimum_block| minimum_block < block_number)
+                    {
+                        stats.past_minimum_block += 1;
+                    }
+                }
+                VrfStatus::Fulfilled if seconds_between(request.updated_at, now) <= window => {
+                    stats
+                        .latencies
+                        .push(seconds_between(request.created_at, request.updated_at));
+                }
+                _ => {}
+            }
+        }
+
+        stats.latencies.sort_unstable();
+        stats
+    }
+
+    /// Returns the given quantile of the fulfillment latency (nearest rank),
+    /// `None` when no request was fulfilled in the window.
+    pub fn latency_quantile(&self, quantile: f64) -> Option<u64> {
+        let rank = (quantile * self.latencies.len() as f64).ceil() as usize;
+        self.latencies.get(rank.max(1) - 1).copied()
+    }
+}
+
+/// Returns the number of seconds from `from` to `to`, 0 if `to` is before `from`.
+fn seconds_between(from: NaiveDateTime, to: NaiveDateTime) -> u64 {
+    (to - from).num_seconds().max(0) as u64
+}
@@ -1,44 +1,45 @@
-use bigdecimal::{Num, ToPrimitive};
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Num, ToPrimitive};
 use num_bigint::BigInt;
 use starknet::{
     core::types::{BlockId, BlockTag},
     providers::SequencerGatewayProvider,
 };
+use url::Url;
 
 use crate::{
-    config::get_config,
+    config::Config,
     constants::{
         API_NUM_SOURCES, API_PRICE_DEVIATION, API_SEQUENCER_DEVIATION, API_TIME_SINCE_LAST_UPDATE,
     },
     error::MonitoringError,
     monitoring::{
-        price_deviation::raw_price_deviation, time_since_last_update::raw_time_since_last_update,
+        normalize_price, price_deviation::raw_price_deviation,
+        time_since_last_update::raw_time_since_last_update,
     },
-    processing::common::query_pragma_api,
+    processing::common::{export_reference_deviation, query_pragma_api},
+    reference::reference_provider,
 };
 
-pub async fn process_data_by_pair(pair: String) -> Result<(), MonitoringError> {
+pub async fn process_data_by_pair(
+    config: Arc<Config>,
+    pair: String,
+) -> Result<(), MonitoringError> {
     // Query the Pragma API
-    let config = get_config(None).await;
     let network_env = &config.network_str();
 
-    let result = query_pragma_api(&pair, network_env, "median", "1min").await?;
+    let result = query_pragma_api(&config, &pair, "median", "1min").await?;
 
     log::info!("Processing data for pair: {}", pair);
 
     // Parse the hex string price
-    let parsed_price = BigInt::from_str_radix(&result.price[2..], 16)
-        .unwrap()
-        .to_string();
-    let normalized_price =
-        parsed_price.to_string().parse::<f64>().unwrap() / 10_f64.powi(result.decimals as i32);
+    let normalized_price = parse_hex_price(&result.price, result.decimals)?;
 
-    let price_deviation = raw_price_deviation(&pair, normalized_price).await?;
+    let price_deviation = raw_price_deviation(reference_provider(), &pair, normalized_price).await;
     let time_since_last_update = raw_time_since_last_update(result.timestamp)?;
 
-    API_PRICE_DEVIATION
-        .with_label_values(&[network_env, &pair])
-        .set(price_deviation);
+    export_reference_deviation(&API_PRICE_DEVIATION, &[network_env, &pair], price_deviation)?;
     API_TIME_SINCE_LAST_UPDATE
         .with_label_values(&[network_env, &pair])
         .set(time_since_last_update as f64);
@@ -49,29 +50,36 @@ pub async fn process_data_by_pair(pair: String) -> Result<(), MonitoringError> {
     Ok(())
 }
 
-pub async fn process_sequencer_data() -> Result<(), MonitoringError> {
+pub async fn process_sequencer_data(config: Arc<Config>) -> Result<(), MonitoringError> {
     let pair = "ETH/STRK".to_string();
 
     // Query the Pragma API
-    let config = get_config(None).await;
     let network_env = config.network_str();
 
-    let result = query_pragma_api(&pair, network_env, "twap", "2h").await?;
+    let result = query_pragma_api(&config, &pair, "twap", "2h").await?;
 
     log::info!("Processing sequencer data");
 
     // Parse the hex string price
-    let parsed_price = BigInt::from_str_radix(&result.price[2..], 16)
-        .unwrap()
-        .to_string();
-    let normalized_price =
-        parsed_price.to_string().parse::<f64>().unwrap() / 10_f64.powi(result.decimals as i32);
-
-    let provider = match network_env {
-        "Testnet" => SequencerGatewayProvider::starknet_alpha_sepolia(),
-        "Mainnet" => SequencerGatewayProvider::starknet_alpha_mainnet(),
-        _ => panic!("Invalid network env"),
+    let normalized_price = parse_hex_price(&result.price, result.decimals)?;
+
+    let sequencer_url = config
+        .network()
+        .sequencer_url
+        .as_ref()
+        .ok_or(MonitoringError::Api(format!(
+            "No sequencer url configured for network {}",
+            network_env
+        )))?;
+    let gateway_url = |path: &str| {
+        Url::parse(&format!("{}/{path}", sequencer_url.trim_end_matches('/')))
+            .map_err(|e| MonitoringError::Api(e.to_string()))
     };
+    let provider = SequencerGatewayProvider::new(
+        gateway_url("gateway")?,
+        gateway_url("feeder_gateway")?,
+        config.network().chain_id,
+    );
 
     #[allow(deprecated)]
     let block = provider
@@ -93,3 +101,12 @@ pub async fn process_sequencer_data() -> Result<(), MonitoringError> {
 
     Ok(())
 }
+
+/// Parses a hex encoded price returned by the Pragma API and normalizes it.
+fn parse_hex_price(price: &str, decimals: u32) -> Result<f64, MonitoringError> {
+    let raw_price = BigInt::from_str_radix(price.trim_start_matches("0x"), 16).map_err(|e| {
+        MonitoringError::Conversion(format!("Failed to parse hex price {:?}: {e}", price))
+    })?;
+
+    normalize_price(&BigDecimal::from(raw_price), decimals)
+}
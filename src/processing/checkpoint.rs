@@ -0,0 +1,101 @@
+use bigdecimal::ToPrimitive;
+use chrono::Utc;
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::AsyncPgConnection;
+use std::sync::Arc;
+
+use crate::config::{Config, DataType};
+use crate::constants::{
+    CHECKPOINT_MEDIAN_DEVIATION, CHECKPOINT_NUM_SOURCES, CHECKPOINT_PRICE_DEVIATION,
+    CHECKPOINT_SENDER, CHECKPOINT_TIME_SINCE_LAST_UPDATE,
+};
+use crate::error::MonitoringError;
+use crate::models::SpotCheckpoint;
+use crate::monitoring::{normalize_price, on_chain_median};
+use crate::processing::common::export_reference_deviation;
+use crate::processing::query::{latest_entry, EntryFilter};
+use crate::processing::stale_metrics::retain_series;
+use crate::reference::reference_provider;
+
+/// Checks the last checkpoint of a spot pair.
+/// Returns the time since the last checkpoint (in seconds).
+pub async fn process_checkpoint_by_pair(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: Arc<Config>,
+    pair: String,
+) -> Result<u64, MonitoringError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| MonitoringError::Connection("Failed to get connection".to_string()))?;
+
+    let checkpoint: SpotCheckpoint = latest_entry(
+        &mut conn,
+        config.checkpoint_table_name(),
+        EntryFilter {
+            pair_id: Some(&pair),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    log::info!("Processing checkpoint for pair: {}", pair);
+
+    let network_env = config.network_str();
+    let labels = [network_env, pair.as_str()];
+
+    let checkpoint_timestamp = checkpoint.timestamp.timestamp();
+    // Clamped as the checkpoint can be slightly in the future (clock skew)
+    let seconds_since_last_checkpoint =
+        (Utc::now().timestamp() - checkpoint_timestamp).max(0) as u64;
+    CHECKPOINT_TIME_SINCE_LAST_UPDATE
+        .with_label_values(&labels)
+        .set(seconds_since_last_checkpoint as f64);
+
+    let num_sources = checkpoint.nb_sources_aggregated.to_i64().ok_or_else(|| {
+        MonitoringError::Conversion(format!(
+            "Failed to convert number of sources {}",
+            checkpoint.nb_sources_aggregated
+        ))
+    })?;
+    CHECKPOINT_NUM_SOURCES
+        .with_label_values(&labels)
+        .set(num_sources);
+
+    // Only keep the series of the latest sender of the pair
+    retain_series(&CHECKPOINT_SENDER, network_env, |series| {
+        series.get("pair") != Some(&pair.as_str())
+            || series.get("sender_address") == Some(&checkpoint.sender_address.as_str())
+    });
+    CHECKPOINT_SENDER
+        .with_label_values(&[network_env, &pair, &checkpoint.sender_address])
+        .set(1);
+
+    let decimals =
+        *config
+            .decimals(DataType::Spot)
+            .get(&pair)
+            .ok_or(MonitoringError::Conversion(format!(
+                "Failed to get decimals for pair {:?}",
+                pair
+            )))?;
+    let checkpoint_price = normalize_price(&checkpoint.price, decimals)?;
+
+    let median = on_chain_median(&config, &pair, DataType::Spot).await?;
+    CHECKPOINT_MEDIAN_DEVIATION
+        .with_label_values(&labels)
+        .set((checkpoint_price - median.price) / median.price);
+
+    let reference_deviation = reference_provider()
+        .historical_price(&pair, checkpoint_timestamp as u64)
+        .await
+        .map(|reference| {
+            (
+                (checkpoint_price - reference.price) / reference.price,
+                reference,
+            )
+        });
+    export_reference_deviation(&CHECKPOINT_PRICE_DEVIATION, &labels, reference_deviation)?;
+
+    Ok(seconds_since_last_checkpoint)
+}
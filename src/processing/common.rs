@@ -1,101 +1,19 @@
+use std::sync::Arc;
+
 use crate::monitoring::publisher_balance;
+use crate::reference::{reference_provider, PrefetchedPrices, ReferencePrice, REFERENCES};
 use crate::{
-    config::{get_config, DataType},
-    constants::{INDEXER_BLOCKS_LEFT, PUBLISHER_BALANCE},
+    config::{Config, DataType},
+    constants::{
+        PUBLISHER_BALANCE, REFERENCE_PRICE_SPREAD, THRESHOLD_NUM_SOURCES,
+        THRESHOLD_ON_OFF_DEVIATION, THRESHOLD_PRICE_DEVIATION, THRESHOLD_SOURCE_DEVIATION,
+        THRESHOLD_TIME_SINCE_LAST_UPDATE,
+    },
     error::MonitoringError,
 };
+use prometheus::GaugeVec;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use serde::{Deserialize, Serialize};
 use starknet::core::types::Felt;
-use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
-#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
-pub struct IndexerServerStatus {
-    pub status: i32,
-    pub starting_block: Option<u64>,
-    pub current_block: Option<u64>,
-    pub head_block: Option<u64>,
-    #[serde(rename = "reason")]
-    pub reason_: Option<String>,
-}
-
-/// Checks if indexers of the given data type are still syncing
-/// Returns true if any of the indexers is still syncing
-pub async fn is_syncing(data_type: &DataType) -> Result<bool, MonitoringError> {
-    let config = get_config(None).await;
-
-    let table_name = config.table_name(data_type.clone());
-
-    let status = get_sink_status(&table_name, config.indexer_url()).await?;
-
-    let provider = &config.network().provider;
-
-    let blocks_left = blocks_left(&status, provider).await?;
-
-    // Update the prometheus metric
-    INDEXER_BLOCKS_LEFT
-        .with_label_values(&[
-            (&config.network().name).into(),
-            &data_type.to_string().to_ascii_lowercase(),
-        ])
-        .set(blocks_left.unwrap_or(0) as i64);
-
-    // Check if any indexer is still syncing
-    Ok(blocks_left.is_some())
-}
-
-/// Returns the status of the indexer
-///
-/// # Arguments
-///
-/// * `table_name` - The name of the table to check
-/// * `base_url` - The base url of the indexer server
-async fn get_sink_status(
-    table_name: &str,
-    base_url: &str,
-) -> Result<IndexerServerStatus, MonitoringError> {
-    let request_url = format!(
-        "{base_url}/status/table/{table_name}",
-        base_url = base_url,
-        table_name = table_name
-    );
-
-    let response = reqwest::get(&request_url)
-        .await
-        .map_err(|e| MonitoringError::Api(e.to_string()))?;
-
-    let status = response
-        .json::<IndexerServerStatus>()
-        .await
-        .map_err(|e| MonitoringError::Api(e.to_string()))?;
-
-    Ok(status)
-}
-
-/// Returns the number of blocks left to sync
-/// Returns None if the indexer is synced
-///
-/// # Arguments
-///
-/// * `sink_status` - The status of the indexer
-/// * `provider` - The provider to check the current block number
-async fn blocks_left(
-    sink_status: &IndexerServerStatus,
-    provider: &JsonRpcClient<HttpTransport>,
-) -> Result<Option<u64>, MonitoringError> {
-    // Safe to unwrap as defined by apibara spec
-    let block_n = sink_status.current_block.unwrap();
-    let current_block = provider
-        .block_number()
-        .await
-        .map_err(MonitoringError::Provider)?;
-
-    if block_n < current_block {
-        Ok(Some(current_block - block_n))
-    } else {
-        Ok(None)
-    }
-}
-
 /// Data Transfer Object for Pragma API
 /// e.g
 /// {
@@ -115,33 +33,33 @@ pub struct PragmaDataDTO {
     pub decimals: u32,
 }
 
-/// Queries Pragma API
+/// Queries Pragma API of the network
 pub async fn query_pragma_api(
+    config: &Config,
     pair: &str,
-    network_env: &str,
     aggregation: &str,
     interval: &str,
 ) -> Result<PragmaDataDTO, MonitoringError> {
-    let request_url = match network_env {
-        "Testnet" => format!(
-            "https://api.dev.pragma.build/node/v1/data/{pair}?aggregation={aggregation}&interval={interval}&routing=true",
-            pair = pair,
-        ),
-        "Mainnet" => format!(
-            "https://api.prod.pragma.build/node/v1/data/{pair}?aggregation={aggregation}&interval={interval}&routing=true",
-            pair = pair,
-            aggregation = aggregation,
-            interval = interval,
-        ),
-        _ => panic!("Invalid network env"),
-    };
+    let api_url = config
+        .network()
+        .api_url
+        .as_ref()
+        .ok_or(MonitoringError::Api(format!(
+            "No API url configured for network {}",
+            config.network_str()
+        )))?;
+    let request_url = format!(
+        "{api_url}/data/{pair}?aggregation={aggregation}&interval={interval}&routing=true",
+    );
 
     // Set headers
     let mut headers = HeaderMap::new();
-    let api_key = std::env::var("PRAGMA_API_KEY").expect("PRAGMA_API_KEY must be set");
+    let api_key = std::env::var("PRAGMA_API_KEY")
+        .map_err(|_| MonitoringError::Api("PRAGMA_API_KEY must be set".to_string()))?;
     headers.insert(
         HeaderName::from_static("x-api-key"),
-        HeaderValue::from_str(&api_key).expect("Failed to parse api key"),
+        HeaderValue::from_str(&api_key)
+            .map_err(|e| MonitoringError::Api(format!("Invalid PRAGMA_API_KEY: {e}")))?,
     );
 
     let client = reqwest::Client::new();
@@ -169,11 +87,11 @@ pub async fn query_pragma_api(
 }
 
 pub async fn check_publisher_balance(
+    config: Arc<Config>,
     publisher: String,
     publisher_address: Felt,
 ) -> Result<(), MonitoringError> {
-    let config = get_config(None).await;
-    let balance = publisher_balance(publisher_address).await?;
+    let balance = publisher_balance(&config, publisher_address).await?;
 
     let network_env = &config.network_str();
 
@@ -182,3 +100,112 @@ pub async fn check_publisher_balance(
         .set(balance);
     Ok(())
 }
+
+/// Exports a deviation computed against a reference price, `labels` must start
+/// with the network and the pair. Only the series of the reference actually used is kept.
+/// When the references do not agree on a price, the deviation is no longer exported
+/// and only the spread between the references is.
+pub fn export_reference_deviation(
+    gauge: &GaugeVec,
+    labels: &[&str],
+    deviation: Result<(f64, ReferencePrice), MonitoringError>,
+) -> Result<(), MonitoringError> {
+    match deviation {
+        Ok((deviation, reference)) => {
+            for other in REFERENCES.iter().filter(|r| **r != reference.reference) {
+                let _ = gauge.remove_label_values(&[labels, &[other]].concat());
+            }
+            gauge
+                .with_label_values(&[labels, &[reference.reference]].concat())
+                .set(deviation);
+            if let Some(spread) = reference.spread {
+                REFERENCE_PRICE_SPREAD
+                    .with_label_values(&labels[..2])
+                    .set(spread);
+            }
+            Ok(())
+        }
+        Err(MonitoringError::NoConsensus { agreeing, spread }) => {
+            log::warn!(
+                "[REFERENCE] No consensus for pair {}: only {} references agree (spread {})",
+                labels[1],
+                agreeing,
+                spread
+            );
+            for reference in REFERENCES {
+                let _ = gauge.remove_label_values(&[labels, &[reference]].concat());
+            }
+            REFERENCE_PRICE_SPREAD
+                .with_label_values(&labels[..2])
+                .set(spread);
+            Ok(())
+        }
+        Err(MonitoringError::MissingReferenceId(_)) => {
+            // Already reported when loading the configuration
+            for reference in REFERENCES {
+                let _ = gauge.remove_label_values(&[labels, &[reference]].concat());
+            }
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Exports the alerting thresholds of the pairs (and of their sources) of the given
+/// type of data, so the alerting rules can compare the metrics against them.
+pub fn export_thresholds(config: &Config, data_type: &DataType) {
+    let network = config.network_str();
+    let type_label = data_type.to_string().to_ascii_lowercase();
+
+    for (pair, sources) in config.sources(data_type.clone()) {
+        let thresholds = config.thresholds(data_type.clone(), pair, None);
+        let labels = [network, pair, &type_label];
+        THRESHOLD_ON_OFF_DEVIATION
+            .with_label_values(&labels)
+            .set(thresholds.on_off_deviation);
+        THRESHOLD_TIME_SINCE_LAST_UPDATE
+            .with_label_values(&labels)
+            .set(thresholds.time_since_last_update as f64);
+        THRESHOLD_NUM_SOURCES
+            .with_label_values(&labels)
+            .set(thresholds.num_sources as i64);
+
+        for source in sources {
+            let thresholds = config.thresholds(data_type.clone(), pair, Some(source));
+            let labels = [network, pair, source, &type_label];
+            THRESHOLD_PRICE_DEVIATION
+                .with_label_values(&labels)
+                .set(thresholds.price_deviation);
+            THRESHOLD_SOURCE_DEVIATION
+                .with_label_values(&labels)
+                .set(thresholds.source_deviation);
+        }
+    }
+}
+
+/// Fetches the reference prices of all the given pairs in a single batched request.
+/// The prices at the current timestamp are passed to the per pair deviations of the
+/// tick, see `prefetched_price`, while the latest prices are served from the cache.
+///
+/// # Arguments
+///
+/// * `pairs` - The pairs to fetch.
+/// * `current` - Whether to fetch the latest prices or the prices at the current timestamp.
+pub async fn prefetch_reference_prices(pairs: &[String], current: bool) -> PrefetchedPrices {
+    let provider = reference_provider();
+    let now = chrono::Utc::now().timestamp() as u64;
+    let prices = if current {
+        provider.current_prices(pairs).await
+    } else {
+        provider.historical_prices(pairs, now).await
+    };
+
+    for (pair, price) in &prices {
+        match price {
+            // Already reported when loading the configuration
+            Ok(_) | Err(MonitoringError::MissingReferenceId(_)) => {}
+            Err(e) => log::warn!("[REFERENCE] Failed to prefetch reference price for {pair}: {e}"),
+        }
+    }
+    PrefetchedPrices::new(now, prices)
+}
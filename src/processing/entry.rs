@@ -0,0 +1,221 @@
+extern crate diesel;
+extern crate dotenv;
+
+use crate::config::{Check, Config};
+use crate::constants::NUM_SOURCES;
+use crate::constants::ON_OFF_PRICE_DEVIATION;
+use crate::constants::PAIR_PRICE;
+use crate::constants::PRICE_DEVIATION;
+use crate::constants::PRICE_DEVIATION_SOURCE;
+use crate::constants::TIME_SINCE_LAST_UPDATE_PAIR_ID;
+use crate::constants::TIME_SINCE_LAST_UPDATE_PUBLISHER;
+use crate::error::MonitoringError;
+use crate::monitoring::{
+    normalize_price, on_chain_median, on_off_price_deviation, price_deviation, source_deviation,
+    time_since_last_update,
+};
+use crate::processing::common::export_reference_deviation;
+use crate::processing::query::{latest_entry, EntryFilter};
+use crate::reference::{data_type_reference_provider, PrefetchedPrices};
+use crate::types::Entry;
+
+use diesel::pg::Pg;
+use diesel::QueryableByName;
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::AsyncPgConnection;
+use std::sync::Arc;
+
+/// Entries that can be read from any spot/future table of the indexer.
+pub trait IndexedEntry: Entry + QueryableByName<Pg> + Send + Sync + 'static {}
+
+impl<T: Entry + QueryableByName<Pg> + Send + Sync + 'static> IndexedEntry for T {}
+
+/// Returns the `data_type` label of the metrics of the entry.
+fn data_type_label<T: Entry>() -> String {
+    T::data_type().to_string().to_ascii_lowercase()
+}
+
+pub async fn process_data_by_pair<T: IndexedEntry>(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: Arc<Config>,
+    pair: String,
+    references: Arc<PrefetchedPrices>,
+) -> Result<u64, MonitoringError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| MonitoringError::Connection("Failed to get connection".to_string()))?;
+
+    let data_type = T::data_type();
+    let data: T = latest_entry(
+        &mut conn,
+        config.table_name(data_type.clone()),
+        EntryFilter {
+            pair_id: Some(&pair),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    log::info!("Processing data for pair: {}", pair);
+
+    let network_env = &config.network_str();
+    let data_type_label = &data_type_label::<T>();
+
+    let seconds_since_last_publish = time_since_last_update(&data);
+    let time_labels =
+        TIME_SINCE_LAST_UPDATE_PAIR_ID.with_label_values(&[network_env, &pair, data_type_label]);
+    let num_sources_labels = NUM_SOURCES.with_label_values(&[network_env, &pair, data_type_label]);
+
+    time_labels.set(seconds_since_last_publish as f64);
+
+    // Publish the number of sources even if the reference is unavailable
+    let median = on_chain_median(&config, &pair, data_type.clone()).await?;
+    num_sources_labels.set(median.num_sources_aggregated as i64);
+
+    if config.is_check_enabled(data_type.clone(), &pair, Check::OnOffDeviation) {
+        let on_off_deviation = on_off_price_deviation(
+            data_type_reference_provider(&data_type),
+            &references,
+            &pair,
+            median.price,
+            data.timestamp().timestamp() as u64,
+        )
+        .await;
+
+        export_reference_deviation(
+            &ON_OFF_PRICE_DEVIATION,
+            &[network_env, &pair, data_type_label],
+            on_off_deviation,
+        )?;
+    }
+
+    Ok(seconds_since_last_publish)
+}
+
+pub async fn process_data_by_pair_and_sources<T: IndexedEntry>(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: Arc<Config>,
+    pair: String,
+    sources: Vec<String>,
+    references: Arc<PrefetchedPrices>,
+) -> Result<u64, MonitoringError> {
+    let mut timestamps = Vec::new();
+
+    let decimals = *config.decimals(T::data_type()).get(&pair.clone()).unwrap();
+
+    for src in sources {
+        log::info!("Processing data for pair: {} and source: {}", pair, src);
+        let res = process_data_by_pair_and_source::<T>(
+            pool.clone(),
+            &config,
+            &pair,
+            &src,
+            decimals,
+            &references,
+        )
+        .await?;
+        timestamps.push(res);
+    }
+
+    Ok(*timestamps.last().unwrap())
+}
+
+pub async fn process_data_by_pair_and_source<T: IndexedEntry>(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &Config,
+    pair: &str,
+    src: &str,
+    decimals: u32,
+    references: &PrefetchedPrices,
+) -> Result<u64, MonitoringError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| MonitoringError::Connection("Failed to get connection".to_string()))?;
+
+    let data_type = T::data_type();
+    let data: T = latest_entry(
+        &mut conn,
+        config.table_name(data_type.clone()),
+        EntryFilter {
+            pair_id: Some(pair),
+            source: Some(src),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let network_env = &config.network_str();
+    let data_type_label = &data_type_label::<T>();
+
+    // Get the labels
+    let price_labels = PAIR_PRICE.with_label_values(&[network_env, pair, src, data_type_label]);
+    let source_deviation_labels =
+        PRICE_DEVIATION_SOURCE.with_label_values(&[network_env, pair, src, data_type_label]);
+
+    // Compute metrics
+    let time = time_since_last_update(&data);
+    let normalized_price = normalize_price(&data.price(), decimals)?;
+
+    // Set the metrics
+    price_labels.set(normalized_price);
+
+    if config.is_check_enabled(data_type.clone(), pair, Check::PriceDeviation) {
+        let deviation = price_deviation(
+            data_type_reference_provider(&data_type),
+            references,
+            &data,
+            normalized_price,
+        )
+        .await;
+        export_reference_deviation(
+            &PRICE_DEVIATION,
+            &[network_env, pair, src, data_type_label],
+            deviation,
+        )?;
+    }
+
+    if config.is_check_enabled(data_type, pair, Check::SourceDeviation) {
+        let (source_deviation, _) = source_deviation(config, &data, normalized_price).await?;
+        source_deviation_labels.set(source_deviation);
+    }
+
+    Ok(time)
+}
+
+pub async fn process_data_by_publisher<T: IndexedEntry>(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: Arc<Config>,
+    publisher: String,
+) -> Result<(), MonitoringError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| MonitoringError::Connection("Failed to get connection".to_string()))?;
+
+    let data: T = latest_entry(
+        &mut conn,
+        config.table_name(T::data_type()),
+        EntryFilter {
+            publisher: Some(&publisher),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    log::info!("Processing data for publisher: {}", publisher);
+
+    let network_env = &config.network_str();
+
+    let seconds_since_last_publish = time_since_last_update(&data);
+    let time_labels = TIME_SINCE_LAST_UPDATE_PUBLISHER.with_label_values(&[
+        network_env,
+        &publisher,
+        &data_type_label::<T>(),
+    ]);
+
+    time_labels.set(seconds_since_last_publish as f64);
+
+    Ok(())
+}
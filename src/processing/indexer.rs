@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::AsyncPgConnection;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{BlockId, MaybePendingBlockWithTxHashes};
+use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+
+use crate::config::{get_config, Config, DataType, SyncingPolicy};
+use crate::constants::{
+    INDEXER_BLOCKS_LEFT, INDEXER_DEGRADED, INDEXER_LAST_BLOCK_TIMESTAMP, INDEXER_STATUS,
+};
+use crate::error::MonitoringError;
+use crate::processing::query::latest_indexed_block;
+use crate::processing::stale_metrics::retain_series;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexerServerStatus {
+    pub status: i32,
+    pub starting_block: Option<u64>,
+    pub current_block: Option<u64>,
+    pub head_block: Option<u64>,
+    #[serde(rename = "reason")]
+    pub reason_: Option<String>,
+}
+
+/// Indexing status of a table, see `check_table_status`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableStatus {
+    /// Number of blocks left to index, `None` when unknown.
+    pub blocks_left: Option<u64>,
+    /// Timestamp of the last indexed block. (in seconds)
+    pub last_block_timestamp: Option<u64>,
+    /// Status and reason reported by the indexer service, `None` when the
+    /// service is unavailable and the status was measured from the database.
+    pub indexer_status: Option<(i32, Option<String>)>,
+}
+
+impl TableStatus {
+    /// Whether the status was measured from the database (degraded mode).
+    pub fn is_degraded(&self) -> bool {
+        self.indexer_status.is_none()
+    }
+
+    /// Whether the indexer is still syncing, or its lag is unknown. When measured from
+    /// the database, the latest row can be a few blocks behind the head even if the
+    /// indexer is synced.
+    pub fn is_syncing(&self) -> bool {
+        let tolerance = match self.is_degraded() {
+            true => DATABASE_SYNC_TOLERANCE,
+            false => 0,
+        };
+        self.blocks_left
+            .is_none_or(|blocks_left| blocks_left > tolerance)
+    }
+}
+
+/// Number of blocks the latest row of a table can be behind the head while its
+/// indexer is still considered synced, when measured from the database.
+pub const DATABASE_SYNC_TOLERANCE: u64 = 10;
+
+/// Number of indexer intervals after which the status of a table is checked again
+/// by the data checks, e.g when the indexer monitoring is stalled.
+const TABLE_STATUS_MAX_AGE_INTERVALS: u64 = 3;
+
+lazy_static! {
+    /// Latest status of the indexed tables and the time it was checked, by network and table name.
+    static ref TABLE_STATUSES: Mutex<HashMap<(String, String), (Instant, TableStatus)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Checks the indexing status of a table and exports it.
+/// The status is read from the indexer service, or measured from the database
+/// when the service is unavailable. It is kept for the data checks, see `is_syncing`.
+pub async fn check_table_status(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &Config,
+    table_name: &str,
+) -> Result<TableStatus, MonitoringError> {
+    let key = (config.network_str().to_string(), table_name.to_string());
+    let status = match fetch_table_status(pool, config, table_name).await {
+        Ok(status) => status,
+        Err(e) => {
+            TABLE_STATUSES.lock().unwrap().remove(&key);
+            return Err(e);
+        }
+    };
+
+    let network_env = config.network_str();
+    let labels = [network_env, table_name];
+    match status.blocks_left {
+        Some(blocks_left) => INDEXER_BLOCKS_LEFT
+            .with_label_values(&labels)
+            .set(blocks_left as i64),
+        None => {
+            let _ = INDEXER_BLOCKS_LEFT.remove_label_values(&labels);
+        }
+    }
+    match status.last_block_timestamp {
+        Some(timestamp) => INDEXER_LAST_BLOCK_TIMESTAMP
+            .with_label_values(&labels)
+            .set(timestamp as f64),
+        None => {
+            let _ = INDEXER_LAST_BLOCK_TIMESTAMP.remove_label_values(&labels);
+        }
+    }
+    INDEXER_DEGRADED
+        .with_label_values(&labels)
+        .set(status.is_degraded() as i64);
+
+    // Only keep the series of the current reason, if the service reported one
+    let reason = status
+        .indexer_status
+        .as_ref()
+        .map(|(_, reason)| reason.as_deref().unwrap_or_default());
+    retain_series(&INDEXER_STATUS, network_env, |labels| {
+        labels.get("table") != Some(&table_name) || labels.get("reason").copied() == reason
+    });
+    if let Some((status, reason)) = &status.indexer_status {
+        INDEXER_STATUS
+            .with_label_values(&[
+                network_env,
+                table_name,
+                reason.as_deref().unwrap_or_default(),
+            ])
+            .set(*status as i64);
+    }
+
+    TABLE_STATUSES
+        .lock()
+        .unwrap()
+        .insert(key, (Instant::now(), status.clone()));
+    Ok(status)
+}
+
+/// Returns the status of the indexer of the given table, as checked by the
+/// indexer monitoring (or checking it when not known yet, or when the last check
+/// is older than `TABLE_STATUS_MAX_AGE_INTERVALS` indexer intervals).
+pub async fn table_status(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &Config,
+    table_name: &str,
+) -> Result<TableStatus, MonitoringError> {
+    let key = (config.network_str().to_string(), table_name.to_string());
+    let max_age = Duration::from_secs(
+        get_config(None).await.intervals().indexer * TABLE_STATUS_MAX_AGE_INTERVALS,
+    );
+    let status = TABLE_STATUSES
+        .lock()
+        .unwrap()
+        .get(&key)
+        .filter(|(checked_at, _)| checked_at.elapsed() < max_age)
+        .map(|(_, status)| status.clone());
+    let status = match status {
+        Some(status) => status,
+        None => check_table_status(pool, config, table_name).await?,
+    };
+
+    if status.is_degraded() {
+        log::warn!(
+            "[{}] Indexer service unavailable, {} freshness measured from the database (degraded)",
+            config.network_str(),
+            table_name
+        );
+    }
+    Ok(status)
+}
+
+/// Whether a tick of a data check runs, see `tick_decision`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickDecision {
+    /// The indexer is synced.
+    Run,
+    /// The indexer is not synced (or its status is unknown) but the policy allows the tick.
+    RunUnsynced,
+    /// The tick is skipped for the given reason.
+    Skip(&'static str),
+}
+
+/// Applies the syncing policy to the status of the indexer of a table,
+/// `None` when the status could not be checked.
+pub fn tick_decision(policy: SyncingPolicy, status: Option<&TableStatus>) -> TickDecision {
+    match (policy, status) {
+        (_, Some(status)) if !status.is_syncing() => TickDecision::Run,
+        (SyncingPolicy::Run, _) => TickDecision::RunUnsynced,
+        (SyncingPolicy::MaxLag(max_blocks), Some(status))
+            if status
+                .blocks_left
+                .is_some_and(|blocks_left| blocks_left <= max_blocks) =>
+        {
+            TickDecision::RunUnsynced
+        }
+        (_, Some(_)) => TickDecision::Skip("syncing"),
+        (_, None) => TickDecision::Skip("status_unavailable"),
+    }
+}
+
+/// Forgets the statuses of the tables of the network that are no longer read.
+pub fn retain_table_statuses(network: &str, tables: &[&str]) {
+    TABLE_STATUSES
+        .lock()
+        .unwrap()
+        .retain(|(status_network, table), _| {
+            status_network != network || tables.contains(&table.as_str())
+        });
+}
+
+async fn fetch_table_status(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &Config,
+    table_name: &str,
+) -> Result<TableStatus, MonitoringError> {
+    let sink_status = match get_sink_status(table_name, config.indexer_url()).await {
+        Ok(sink_status) => sink_status,
+        Err(e) => {
+            log::warn!(
+                "[{}][INDEXER] Failed to get the status of {table_name} from the indexer service, \
+                 falling back to the database: {e}",
+                config.network_str()
+            );
+            return database_table_status(pool, config, table_name).await;
+        }
+    };
+
+    // Not reported until the indexer has started
+    let Some(current_block) = sink_status.current_block else {
+        log::warn!(
+            "[{}][INDEXER] The indexer service did not report the current block of {table_name}, \
+             falling back to the database",
+            config.network_str()
+        );
+        return database_table_status(pool, config, table_name).await;
+    };
+
+    let provider = &config.network().provider;
+    let blocks_left = blocks_left(current_block, provider).await?;
+    let last_block_timestamp = block_timestamp(provider, current_block).await?;
+
+    Ok(TableStatus {
+        blocks_left: Some(blocks_left.unwrap_or(0)),
+        last_block_timestamp: Some(last_block_timestamp),
+        indexer_status: Some((sink_status.status, sink_status.reason_)),
+    })
+}
+
+/// Measures the status of a table from its latest row, compared to the RPC head.
+/// The VRF requests have no block number, only the time of their latest update is known.
+/// The checkpoints and VRF requests are too sparse for their latest row to tell the lag
+/// of the indexer, only the entries have one.
+async fn database_table_status(
+    pool: &deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: &Config,
+    table_name: &str,
+) -> Result<TableStatus, MonitoringError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| MonitoringError::Connection("Failed to get connection".to_string()))?;
+
+    let latest_block = match config.network().vrf_table.as_deref() == Some(table_name) {
+        true => latest_indexed_block(&mut conn, table_name, false, "updated_at").await?,
+        false => latest_indexed_block(&mut conn, table_name, true, "block_timestamp").await?,
+    };
+    let current_block = config
+        .network()
+        .provider
+        .block_number()
+        .await
+        .map_err(MonitoringError::Provider)?;
+
+    let is_entry_table = [DataType::Spot, DataType::Future]
+        .into_iter()
+        .any(|data_type| config.table_name(data_type) == table_name);
+
+    Ok(TableStatus {
+        blocks_left: latest_block
+            .as_ref()
+            .filter(|_| is_entry_table)
+            .and_then(|block| {
+                let block_number = u64::try_from(block.block_number?).ok()?;
+                Some(current_block.saturating_sub(block_number))
+            }),
+        last_block_timestamp: latest_block
+            .map(|block| block.block_timestamp.timestamp().max(0) as u64),
+        indexer_status: None,
+    })
+}
+
+/// Returns the status of the indexer
+///
+/// # Arguments
+///
+/// * `table_name` - The name of the table to check
+/// * `base_url` - The base url of the indexer server
+async fn get_sink_status(
+    table_name: &str,
+    base_url: &str,
+) -> Result<IndexerServerStatus, MonitoringError> {
+    let request_url = format!(
+        "{base_url}/status/table/{table_name}",
+        base_url = base_url,
+        table_name = table_name
+    );
+
+    let response = reqwest::get(&request_url)
+        .await
+        .map_err(|e| MonitoringError::Api(e.to_string()))?;
+
+    let status = response
+        .json::<IndexerServerStatus>()
+        .await
+        .map_err(|e| MonitoringError::Api(e.to_string()))?;
+
+    Ok(status)
+}
+
+/// Returns the number of blocks left to sync
+/// Returns None if the indexer is synced
+///
+/// # Arguments
+///
+/// * `block_n` - The current block of the indexer
+/// * `provider` - The provider to check the current block number
+async fn blocks_left(
+    block_n: u64,
+    provider: &JsonRpcClient<HttpTransport>,
+) -> Result<Option<u64>, MonitoringError> {
+    let current_block = provider
+        .block_number()
+        .await
+        .map_err(MonitoringError::Provider)?;
+
+    if block_n < current_block {
+        Ok(Some(current_block - block_n))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the timestamp of the given block. (in seconds)
+async fn block_timestamp(
+    provider: &JsonRpcClient<HttpTransport>,
+    block_number: u64,
+) -> Result<u64, MonitoringError> {
+    let block = provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await
+        .map_err(MonitoringError::Provider)?;
+
+    Ok(match block {
+        MaybePendingBlockWithTxHashes::Block(block) => block.timestamp,
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => block.timestamp,
+    })
+}
@@ -1,4 +1,8 @@
 pub mod api;
+pub mod checkpoint;
 pub mod common;
-pub mod future;
-pub mod spot;
+pub mod entry;
+pub mod indexer;
+pub mod query;
+pub mod stale_metrics;
+pub mod vrf;
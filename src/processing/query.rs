@@ -0,0 +1,139 @@
+use chrono::NaiveDateTime;
+use diesel::OptionalExtension;
+use diesel::{
+    pg::Pg,
+    sql_types::{Integer, Text, Timestamp},
+    QueryableByName,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+use crate::error::MonitoringError;
+use crate::models::{IndexedBlock, VrfRequest, VrfRequestCount};
+use crate::monitoring::vrf::VrfStatus;
+
+/// Filters of an entries query, unset ones are ignored.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EntryFilter<'a> {
+    pub pair_id: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub publisher: Option<&'a str>,
+}
+
+/// Returns the latest entry of the given table matching the filter.
+/// The table name comes from the config (see `Config::table_name`), which allows the
+/// same query to read the `SpotEntry`/`FutureEntry` of any network.
+pub async fn latest_entry<T>(
+    conn: &mut AsyncPgConnection,
+    table_name: &str,
+    filter: EntryFilter<'_>,
+) -> Result<T, MonitoringError>
+where
+    T: QueryableByName<Pg> + Send + 'static,
+{
+    if !is_valid_table_name(table_name) {
+        return Err(MonitoringError::InvalidTableName(table_name.to_string()));
+    }
+
+    let conditions = [
+        ("pair_id", filter.pair_id),
+        ("source", filter.source),
+        ("publisher", filter.publisher),
+    ]
+    .into_iter()
+    .filter_map(|(column, value)| Some((column, value?)))
+    .collect::<Vec<_>>();
+
+    let where_clause = conditions
+        .iter()
+        .enumerate()
+        .map(|(i, (column, _))| format!("{column} = ${}", i + 1))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let where_clause = match where_clause.is_empty() {
+        true => String::new(),
+        false => format!("WHERE {where_clause}"),
+    };
+
+    let mut query = diesel::sql_query(format!(
+        "SELECT * FROM \"{table_name}\" {where_clause} ORDER BY block_timestamp DESC LIMIT 1"
+    ))
+    .into_boxed::<Pg>();
+    for (_, value) in conditions {
+        query = query.bind::<Text, _>(value.to_string());
+    }
+
+    Ok(query.get_result::<T>(conn).await?)
+}
+
+/// Returns the current state of the VRF requests that are still pending
+/// or were updated since the given time.
+/// Previous versions of the updated requests (closed `_cursor`) are left out.
+pub async fn recent_vrf_requests(
+    conn: &mut AsyncPgConnection,
+    table_name: &str,
+    since: NaiveDateTime,
+) -> Result<Vec<VrfRequest>, MonitoringError> {
+    if !is_valid_table_name(table_name) {
+        return Err(MonitoringError::InvalidTableName(table_name.to_string()));
+    }
+
+    Ok(diesel::sql_query(format!(
+        "SELECT * FROM \"{table_name}\" \
+         WHERE upper_inf(_cursor) AND (status = $1 OR updated_at >= $2)"
+    ))
+    .bind::<Integer, _>(VrfStatus::Received as i32)
+    .bind::<Timestamp, _>(since)
+    .load::<VrfRequest>(conn)
+    .await?)
+}
+
+/// Returns the number of VRF requests per requestor and status.
+pub async fn vrf_request_counts(
+    conn: &mut AsyncPgConnection,
+    table_name: &str,
+) -> Result<Vec<VrfRequestCount>, MonitoringError> {
+    if !is_valid_table_name(table_name) {
+        return Err(MonitoringError::InvalidTableName(table_name.to_string()));
+    }
+
+    Ok(diesel::sql_query(format!(
+        "SELECT requestor_address, status, COUNT(*) AS count FROM \"{table_name}\" \
+         WHERE upper_inf(_cursor) GROUP BY requestor_address, status"
+    ))
+    .load::<VrfRequestCount>(conn)
+    .await?)
+}
+
+/// Returns the latest block indexed in the given table, `None` if the table is empty.
+/// Tables without block number (e.g the VRF requests) only have the time of their
+/// latest update, given by `timestamp_column`.
+pub async fn latest_indexed_block(
+    conn: &mut AsyncPgConnection,
+    table_name: &str,
+    has_block_number: bool,
+    timestamp_column: &str,
+) -> Result<Option<IndexedBlock>, MonitoringError> {
+    if !is_valid_table_name(table_name) {
+        return Err(MonitoringError::InvalidTableName(table_name.to_string()));
+    }
+
+    let block_number = match has_block_number {
+        true => "block_number",
+        false => "NULL::bigint",
+    };
+    Ok(diesel::sql_query(format!(
+        "SELECT {block_number} AS block_number, {timestamp_column} AS block_timestamp \
+         FROM \"{table_name}\" ORDER BY {timestamp_column} DESC LIMIT 1"
+    ))
+    .get_result::<IndexedBlock>(conn)
+    .await
+    .optional()?)
+}
+
+/// Table names are interpolated in the queries, only plain identifiers are allowed.
+fn is_valid_table_name(table_name: &str) -> bool {
+    !table_name.is_empty()
+        && table_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use prometheus::core::{Collector, MetricVec, MetricVecBuilder};
+
+use crate::config::{Check, Config, DataType};
+use crate::constants::{
+    API_NUM_SOURCES, API_PRICE_DEVIATION, API_SEQUENCER_DEVIATION, API_TIME_SINCE_LAST_UPDATE,
+    CHECKPOINT_MEDIAN_DEVIATION, CHECKPOINT_NUM_SOURCES, CHECKPOINT_PRICE_DEVIATION,
+    CHECKPOINT_SENDER, CHECKPOINT_TIME_SINCE_LAST_UPDATE, INDEXER_BLOCKS_LEFT, INDEXER_DEGRADED,
+    INDEXER_LAST_BLOCK_TIMESTAMP, INDEXER_STATUS, MISSING_REFERENCE_ID, MONITOR_RUNNING_UNSYNCED,
+    MONITOR_SKIPPED_TICKS, NUM_SOURCES, ON_OFF_PRICE_DEVIATION, PAIR_PRICE, PRICE_DEVIATION,
+    PRICE_DEVIATION_SOURCE, PUBLISHER_BALANCE, REFERENCE_PRICE_SPREAD, THRESHOLD_NUM_SOURCES,
+    THRESHOLD_ON_OFF_DEVIATION, THRESHOLD_PRICE_DEVIATION, THRESHOLD_SOURCE_DEVIATION,
+    THRESHOLD_TIME_SINCE_LAST_UPDATE, TIME_SINCE_LAST_UPDATE_PAIR_ID,
+    TIME_SINCE_LAST_UPDATE_PUBLISHER, VRF_FULFILLMENT_LATENCY, VRF_OLDEST_PENDING_REQUEST,
+    VRF_PENDING_REQUESTS, VRF_REQUESTS, VRF_REQUESTS_BY_REQUESTOR, VRF_REQUESTS_PAST_MINIMUM_BLOCK,
+};
+
+type Labels<'a> = HashMap<&'a str, &'a str>;
+
+/// Removes the series of the given network for which `keep` returns false.
+pub fn retain_series<T: MetricVecBuilder>(
+    metric: &MetricVec<T>,
+    network: &str,
+    keep: impl Fn(&Labels) -> bool,
+) {
+    for family in metric.collect() {
+        for series in family.get_metric() {
+            let labels = series
+                .get_label()
+                .iter()
+                .map(|label| (label.get_name(), label.get_value()))
+                .collect::<Labels>();
+            if labels.get("network") == Some(&network) && !keep(&labels) {
+                let _ = metric.remove(&labels);
+            }
+        }
+    }
+}
+
+/// Removes the pair metrics of the given type of data whose pair or source is no
+/// longer configured, or whose check has been disabled, so they stop being exported.
+pub fn remove_stale_pair_metrics(config: &Config, data_type: &DataType) {
+    let network = config.network_str();
+    let type_label = data_type.to_string().to_ascii_lowercase();
+    let sources = config.sources(data_type.clone());
+
+    let other_type = |labels: &Labels| labels.get("type") != Some(&type_label.as_str());
+    let has_pair = |labels: &Labels| {
+        labels
+            .get("pair")
+            .is_some_and(|pair| sources.contains_key(*pair))
+    };
+    let has_source = |labels: &Labels| match (labels.get("pair"), labels.get("source")) {
+        (Some(pair), Some(source)) => sources
+            .get(*pair)
+            .is_some_and(|sources| sources.iter().any(|s| s == source)),
+        _ => false,
+    };
+    let is_enabled = |labels: &Labels, check: Check| {
+        labels
+            .get("pair")
+            .is_some_and(|pair| config.is_check_enabled(data_type.clone(), pair, check))
+    };
+
+    retain_series(&TIME_SINCE_LAST_UPDATE_PAIR_ID, network, |labels| {
+        other_type(labels) || has_pair(labels)
+    });
+    retain_series(&NUM_SOURCES, network, |labels| {
+        other_type(labels) || has_pair(labels)
+    });
+    retain_series(&ON_OFF_PRICE_DEVIATION, network, |labels| {
+        other_type(labels) || (has_pair(labels) && is_enabled(labels, Check::OnOffDeviation))
+    });
+    retain_series(&PAIR_PRICE, network, |labels| {
+        other_type(labels) || has_source(labels)
+    });
+    retain_series(&PRICE_DEVIATION, network, |labels| {
+        other_type(labels) || (has_source(labels) && is_enabled(labels, Check::PriceDeviation))
+    });
+    retain_series(&PRICE_DEVIATION_SOURCE, network, |labels| {
+        other_type(labels) || (has_source(labels) && is_enabled(labels, Check::SourceDeviation))
+    });
+    retain_series(&THRESHOLD_ON_OFF_DEVIATION, network, |labels| {
+        other_type(labels) || has_pair(labels)
+    });
+    retain_series(&THRESHOLD_TIME_SINCE_LAST_UPDATE, network, |labels| {
+        other_type(labels) || has_pair(labels)
+    });
+    retain_series(&THRESHOLD_NUM_SOURCES, network, |labels| {
+        other_type(labels) || has_pair(labels)
+    });
+    retain_series(&THRESHOLD_PRICE_DEVIATION, network, |labels| {
+        other_type(labels) || has_source(labels)
+    });
+    retain_series(&THRESHOLD_SOURCE_DEVIATION, network, |labels| {
+        other_type(labels) || has_source(labels)
+    });
+
+    // Metrics shared by both types of data
+    if *data_type == DataType::Spot {
+        let is_configured = |labels: &Labels| {
+            labels.get("pair").is_some_and(|pair| {
+                config.sources(DataType::Spot).contains_key(*pair)
+                    || config.sources(DataType::Future).contains_key(*pair)
+            })
+        };
+        retain_series(&REFERENCE_PRICE_SPREAD, network, is_configured);
+        retain_series(&MISSING_REFERENCE_ID, network, has_pair);
+    }
+}
+
+/// Removes the metrics of the publishers no longer in the registry (or ignored).
+pub fn remove_stale_publisher_metrics(config: &Config) {
+    let network = config.network_str();
+    let publishers = config.all_publishers();
+    let has_publisher = |labels: &Labels| {
+        labels
+            .get("publisher")
+            .is_some_and(|publisher| publishers.contains_key(*publisher))
+    };
+
+    retain_series(&TIME_SINCE_LAST_UPDATE_PUBLISHER, network, has_publisher);
+    retain_series(&PUBLISHER_BALANCE, network, has_publisher);
+}
+
+/// Removes the API metrics of the pairs no longer monitored through the API,
+/// or all of them when the API (or the sequencer) is no longer configured.
+pub fn remove_stale_api_metrics(config: &Config) {
+    let network = config.network_str();
+    let api_enabled = config.network().api_url.is_some();
+    let is_monitored = |labels: &Labels| {
+        api_enabled
+            && labels.get("pair").is_some_and(|pair| {
+                config.sources(DataType::Spot).contains_key(*pair)
+                    && config.is_check_enabled(DataType::Spot, pair, Check::Api)
+            })
+    };
+
+    retain_series(&API_PRICE_DEVIATION, network, is_monitored);
+    retain_series(&API_TIME_SINCE_LAST_UPDATE, network, is_monitored);
+    retain_series(&API_NUM_SOURCES, network, is_monitored);
+    retain_series(&API_SEQUENCER_DEVIATION, network, |_| {
+        api_enabled && config.network().sequencer_url.is_some()
+    });
+}
+
+/// Removes the checkpoint metrics of the spot pairs no longer configured,
+/// or whose checkpoint check has been disabled.
+pub fn remove_stale_checkpoint_metrics(config: &Config) {
+    let network = config.network_str();
+    let is_monitored = |labels: &Labels| {
+        labels.get("pair").is_some_and(|pair| {
+            config.sources(DataType::Spot).contains_key(*pair)
+                && config.is_check_enabled(DataType::Spot, pair, Check::Checkpoint)
+        })
+    };
+
+    retain_series(&CHECKPOINT_TIME_SINCE_LAST_UPDATE, network, is_monitored);
+    retain_series(&CHECKPOINT_MEDIAN_DEVIATION, network, is_monitored);
+    retain_series(&CHECKPOINT_PRICE_DEVIATION, network, is_monitored);
+    retain_series(&CHECKPOINT_NUM_SOURCES, network, is_monitored);
+    retain_series(&CHECKPOINT_SENDER, network, is_monitored);
+}
+
+/// Removes the indexer metrics of the tables that are no longer read.
+pub fn remove_stale_indexer_metrics(config: &Config) {
+    let network = config.network_str();
+    let tables = config.indexed_tables();
+    let is_read = |labels: &Labels| {
+        labels
+            .get("table")
+            .is_some_and(|table| tables.contains(table))
+    };
+
+    retain_series(&INDEXER_BLOCKS_LEFT, network, is_read);
+    retain_series(&INDEXER_LAST_BLOCK_TIMESTAMP, network, is_read);
+    retain_series(&INDEXER_STATUS, network, is_read);
+    retain_series(&INDEXER_DEGRADED, network, is_read);
+}
+
+/// Removes the VRF metrics of the network when its VRF is no longer monitored.
+pub fn remove_stale_vrf_metrics(config: &Config) {
+    if config.network().vrf_table.is_some() {
+        return;
+    }
+    remove_vrf_metrics(config.network_str());
+}
+
+fn remove_vrf_metrics(network: &str) {
+    retain_series(&VRF_PENDING_REQUESTS, network, |_| false);
+    retain_series(&VRF_OLDEST_PENDING_REQUEST, network, |_| false);
+    retain_series(&VRF_REQUESTS_PAST_MINIMUM_BLOCK, network, |_| false);
+    retain_series(&VRF_FULFILLMENT_LATENCY, network, |_| false);
+    retain_series(&VRF_REQUESTS, network, |_| false);
+    retain_series(&VRF_REQUESTS_BY_REQUESTOR, network, |_| false);
+}
+
+/// Removes all the metrics of a network that is no longer configured.
+pub fn remove_network_metrics(network: &str) {
+    retain_series(&TIME_SINCE_LAST_UPDATE_PUBLISHER, network, |_| false);
+    retain_series(&PAIR_PRICE, network, |_| false);
+    retain_series(&TIME_SINCE_LAST_UPDATE_PAIR_ID, network, |_| false);
+    retain_series(&PRICE_DEVIATION, network, |_| false);
+    retain_series(&PRICE_DEVIATION_SOURCE, network, |_| false);
+    retain_series(&NUM_SOURCES, network, |_| false);
+    retain_series(&INDEXER_BLOCKS_LEFT, network, |_| false);
+    retain_series(&INDEXER_LAST_BLOCK_TIMESTAMP, network, |_| false);
+    retain_series(&INDEXER_STATUS, network, |_| false);
+    retain_series(&INDEXER_DEGRADED, network, |_| false);
+    retain_series(&MONITOR_RUNNING_UNSYNCED, network, |_| false);
+    retain_series(&MONITOR_SKIPPED_TICKS, network, |_| false);
+    retain_series(&PUBLISHER_BALANCE, network, |_| false);
+    retain_series(&API_PRICE_DEVIATION, network, |_| false);
+    retain_series(&ON_OFF_PRICE_DEVIATION, network, |_| false);
+    retain_series(&REFERENCE_PRICE_SPREAD, network, |_| false);
+    retain_series(&MISSING_REFERENCE_ID, network, |_| false);
+    retain_series(&API_TIME_SINCE_LAST_UPDATE, network, |_| false);
+    retain_series(&API_NUM_SOURCES, network, |_| false);
+    retain_series(&API_SEQUENCER_DEVIATION, network, |_| false);
+    retain_series(&THRESHOLD_PRICE_DEVIATION, network, |_| false);
+    retain_series(&THRESHOLD_SOURCE_DEVIATION, network, |_| false);
+    retain_series(&THRESHOLD_ON_OFF_DEVIATION, network, |_| false);
+    retain_series(&THRESHOLD_TIME_SINCE_LAST_UPDATE, network, |_| false);
+    retain_series(&THRESHOLD_NUM_SOURCES, network, |_| false);
+    retain_series(&CHECKPOINT_TIME_SINCE_LAST_UPDATE, network, |_| false);
+    retain_series(&CHECKPOINT_MEDIAN_DEVIATION, network, |_| false);
+    retain_series(&CHECKPOINT_PRICE_DEVIATION, network, |_| false);
+    retain_series(&CHECKPOINT_NUM_SOURCES, network, |_| false);
+    retain_series(&CHECKPOINT_SENDER, network, |_| false);
+    remove_vrf_metrics(network);
+}
@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::AsyncPgConnection;
+use starknet::providers::Provider;
+
+use crate::config::Config;
+use crate::constants::{
+    VRF_FULFILLMENT_LATENCY, VRF_OLDEST_PENDING_REQUEST, VRF_PENDING_REQUESTS, VRF_REQUESTS,
+    VRF_REQUESTS_BY_REQUESTOR, VRF_REQUESTS_PAST_MINIMUM_BLOCK,
+};
+use crate::error::MonitoringError;
+use crate::monitoring::vrf::{VrfStats, VrfStatus, VRF_LATENCY_QUANTILES, VRF_LATENCY_WINDOW};
+use crate::processing::query::{recent_vrf_requests, vrf_request_counts};
+use crate::processing::stale_metrics::retain_series;
+
+/// Checks the VRF requests of the network: pending requests, fulfillment latency
+/// and number of requests per status and requestor.
+pub async fn process_vrf_requests(
+    pool: deadpool::managed::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>,
+    config: Arc<Config>,
+) -> Result<(), MonitoringError> {
+    let Some(table_name) = config.network().vrf_table.as_deref() else {
+        return Ok(());
+    };
+
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|_| MonitoringError::Connection("Failed to get connection".to_string()))?;
+
+    log::info!("Processing VRF requests of table: {}", table_name);
+
+    let now = Utc::now().naive_utc();
+    let since = now - Duration::seconds(VRF_LATENCY_WINDOW as i64);
+    let requests = recent_vrf_requests(&mut conn, table_name, since).await?;
+    let block_number = config
+        .network()
+        .provider
+        .block_number()
+        .await
+        .map_err(MonitoringError::Provider)?;
+
+    let network_env = config.network_str();
+    let stats = VrfStats::new(&requests, now, block_number, VRF_LATENCY_WINDOW);
+
+    for (age, count) in &stats.pending_by_age {
+        VRF_PENDING_REQUESTS
+            .with_label_values(&[network_env, age])
+            .set(*count);
+    }
+    VRF_OLDEST_PENDING_REQUEST
+        .with_label_values(&[network_env])
+        .set(stats.oldest_pending as f64);
+    VRF_REQUESTS_PAST_MINIMUM_BLOCK
+        .with_label_values(&[network_env])
+        .set(stats.past_minimum_block);
+
+    for quantile in VRF_LATENCY_QUANTILES {
+        let labels = [network_env, &quantile.to_string()];
+        match stats.latency_quantile(quantile) {
+            Some(latency) => VRF_FULFILLMENT_LATENCY
+                .with_label_values(&labels)
+                .set(latency as f64),
+            // No request fulfilled in the window
+            None => {
+                let _ = VRF_FULFILLMENT_LATENCY.remove_label_values(&labels);
+            }
+        }
+    }
+
+    let counts = vrf_request_counts(&mut conn, table_name).await?;
+
+    let mut by_status = BTreeMap::<VrfStatus, i64>::new();
+    let mut by_requestor = HashSet::new();
+    for count in &counts {
+        let status = VrfStatus::from_code(&count.status);
+        *by_status.entry(status).or_default() += count.count;
+
+        let status: &str = status.into();
+        VRF_REQUESTS_BY_REQUESTOR
+            .with_label_values(&[network_env, &count.requestor_address, status])
+            .set(count.count);
+        by_requestor.insert((count.requestor_address.as_str(), status));
+    }
+    for (status, count) in by_status {
+        VRF_REQUESTS
+            .with_label_values(&[network_env, status.into()])
+            .set(count);
+    }
+    retain_series(&VRF_REQUESTS_BY_REQUESTOR, network_env, |labels| {
+        match (labels.get("requestor"), labels.get("status")) {
+            (Some(requestor), Some(status)) => by_requestor.contains(&(*requestor, *status)),
+            _ => false,
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+
+use super::{ReferencePrice, ReferencePriceProvider};
+use crate::error::MonitoringError;
+
+const BINANCE_FUTURES_BASE_URL: &str = "https://fapi.binance.com/fapi/v1";
+
+/// Data Transfer Object for Binance `premiumIndex` API
+/// e.g
+/// {
+///   "symbol": "BTCUSDT",
+///   "markPrice": "42220.10000000",
+///   "indexPrice": "42231.54255319",
+///   ...
+/// }
+#[allow(unused)]
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PremiumIndexDTO {
+    symbol: String,
+    mark_price: String,
+    index_price: String,
+}
+
+/// Data Transfer Object for Binance `markPriceKlines` API
+/// e.g
+/// [
+///   [1702677600000, "42220.1", "42250.0", "42210.3", "42231.5", "0", 1702677659999, ...]
+/// ]
+/// Only the open price is read.
+pub type KlineDTO = Vec<serde_json::Value>;
+
+/// Reference prices of perpetual futures from the Binance USDⓈ-M futures API.
+/// The mark price of the perpetual contract is used as reference.
+pub struct BinanceFuturesProvider {
+    client: reqwest::Client,
+}
+
+impl BinanceFuturesProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, MonitoringError> {
+        let response = self
+            .client
+            .get(format!("{BINANCE_FUTURES_BASE_URL}/{path}"))
+            .send()
+            .await
+            .map_err(|e| MonitoringError::Api(e.to_string()))?;
+
+        match response.status() {
+            reqwest::StatusCode::OK => response.json::<T>().await.map_err(|e| {
+                MonitoringError::Api(format!(
+                    "Failed to convert to DTO object, got error {:?}",
+                    e.to_string()
+                ))
+            }),
+            other => Err(MonitoringError::Api(format!(
+                "Unexpected response status from binance: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl ReferencePriceProvider for BinanceFuturesProvider {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
+
+    fn reference_id(&self, pair_id: &str) -> Result<String, MonitoringError> {
+        binance_symbol(pair_id)
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let symbol = binance_symbol(pair_id)?;
+
+        // Mark price of the minute containing the timestamp
+        let start_time = (timestamp - timestamp % 60) * 1000;
+        let klines: Vec<KlineDTO> = self
+            .get(&format!(
+                "markPriceKlines?symbol={symbol}&interval=1m&startTime={start_time}&limit=1"
+            ))
+            .await?;
+
+        let open_price = klines
+            .first()
+            .and_then(|kline| kline.get(1))
+            .and_then(|price| price.as_str())
+            .ok_or(MonitoringError::Api(format!(
+                "Failed to get binance mark price for symbol {:?}",
+                symbol
+            )))?;
+
+        Ok(ReferencePrice::new(parse_price(open_price)?, self.name()))
+    }
+
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        let symbol = binance_symbol(pair_id)?;
+
+        let index: PremiumIndexDTO = self.get(&format!("premiumIndex?symbol={symbol}")).await?;
+
+        Ok(ReferencePrice::new(
+            parse_price(&index.mark_price)?,
+            self.name(),
+        ))
+    }
+}
+
+/// Returns the symbol of the perpetual contract of the given pair on Binance.
+/// USD quoted pairs are mapped to their USDT contract.
+/// e.g BTC/USD -> BTCUSDT
+pub fn binance_symbol(pair_id: &str) -> Result<String, MonitoringError> {
+    let (base, quote) = pair_id
+        .split_once('/')
+        .ok_or(MonitoringError::MissingReferenceId(pair_id.to_string()))?;
+
+    let quote = match quote {
+        "USD" => "USDT",
+        quote => quote,
+    };
+
+    Ok(format!("{base}{quote}"))
+}
+
+fn parse_price(price: &str) -> Result<f64, MonitoringError> {
+    price.parse::<f64>().map_err(|e| {
+        MonitoringError::Conversion(format!("Failed to parse binance price {:?}: {e}", price))
+    })
+}
@@ -0,0 +1,252 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use tokio::sync::{Notify, OnceCell};
+
+use super::{ReferencePrice, ReferencePriceProvider, ReferencePrices};
+use crate::error::MonitoringError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    coin_id: String,
+    /// Timestamp bucket, i.e timestamp / bucket duration.
+    bucket: u64,
+    current: bool,
+}
+
+struct CacheEntry {
+    created_at: Instant,
+    price: OnceCell<ReferencePrice>,
+    /// Whether a caller is fetching the price, see `CacheEntry::claim`.
+    fetching: AtomicBool,
+    /// Notified when the caller fetching the price is done.
+    fetched: Notify,
+}
+
+impl CacheEntry {
+    /// Claims the fetching of the price, unless another caller is already fetching it.
+    /// The claim is released when dropped.
+    fn claim(self: &Arc<Self>) -> Option<Claim> {
+        self.fetching
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Claim(self.clone()))
+    }
+
+    /// Waits for the caller fetching the price, if any.
+    async fn wait(&self) {
+        let fetched = self.fetched.notified();
+        tokio::pin!(fetched);
+        fetched.as_mut().enable();
+        if self.fetching.load(Ordering::Acquire) {
+            fetched.await;
+        }
+    }
+}
+
+/// Claim on the fetching of the price of a cache entry.
+struct Claim(Arc<CacheEntry>);
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        self.0.fetching.store(false, Ordering::Release);
+        self.0.fetched.notify_waiters();
+    }
+}
+
+/// Caches the prices of a reference provider by (reference id, timestamp bucket).
+///
+/// Concurrent lookups of the same key are coalesced into a single upstream request,
+/// whether they are batched or not: the first caller fetches the price while the
+/// others wait for it. If the request fails, the next waiting caller retries it.
+pub struct CachedProvider<P> {
+    inner: P,
+    entries: Mutex<HashMap<CacheKey, Arc<CacheEntry>>>,
+    /// Duration of a timestamp bucket, prices within the same bucket are shared.
+    bucket: Duration,
+    /// Duration after which an entry is evicted.
+    ttl: Duration,
+}
+
+impl<P: ReferencePriceProvider> CachedProvider<P> {
+    pub fn new(inner: P, bucket: Duration, ttl: Duration) -> Self {
+        Self {
+            inner,
+            entries: Mutex::new(HashMap::new()),
+            bucket,
+            ttl,
+        }
+    }
+
+    /// Returns the entry of the given key, evicting the expired ones.
+    fn entry(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+        current: bool,
+    ) -> Result<Arc<CacheEntry>, MonitoringError> {
+        let key = CacheKey {
+            coin_id: self.inner.reference_id(pair_id)?,
+            bucket: timestamp / self.bucket.as_secs().max(1),
+            current,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.created_at.elapsed() < self.ttl);
+
+        let entry = entries.entry(key).or_insert_with(|| {
+            Arc::new(CacheEntry {
+                created_at: Instant::now(),
+                price: OnceCell::new(),
+                fetching: AtomicBool::new(false),
+                fetched: Notify::new(),
+            })
+        });
+
+        Ok(entry.clone())
+    }
+
+    /// Returns the prices of the given pairs, from the cache or fetched with `fetch`.
+    /// Only the prices that no other caller is fetching are requested, in a single
+    /// request, while the others are waited for.
+    async fn prices<F, Fut>(
+        &self,
+        pair_ids: &[String],
+        timestamp: u64,
+        current: bool,
+        fetch: F,
+    ) -> ReferencePrices
+    where
+        F: Fn(Vec<String>) -> Fut + Send + Sync,
+        Fut: Future<Output = ReferencePrices> + Send,
+    {
+        let mut results = ReferencePrices::new();
+        let mut pending = Vec::new();
+        for pair_id in pair_ids {
+            match self.entry(pair_id, timestamp, current) {
+                Ok(entry) => pending.push((pair_id.clone(), entry)),
+                Err(e) => {
+                    results.insert(pair_id.clone(), Err(e));
+                }
+            }
+        }
+
+        while !pending.is_empty() {
+            let mut claimed = Vec::new();
+            let mut waiting = Vec::new();
+            for (pair_id, entry) in pending {
+                if let Some(price) = entry.price.get() {
+                    results.insert(pair_id, Ok(price.clone()));
+                } else if let Some(claim) = entry.claim() {
+                    // The price may have been set since, before the previous claim was released
+                    match entry.price.get() {
+                        Some(price) => {
+                            results.insert(pair_id, Ok(price.clone()));
+                        }
+                        None => claimed.push((pair_id, claim)),
+                    }
+                } else {
+                    waiting.push((pair_id, entry));
+                }
+            }
+
+            if !claimed.is_empty() {
+                let claimed_ids = claimed
+                    .iter()
+                    .map(|(pair_id, _)| pair_id.clone())
+                    .collect::<Vec<String>>();
+                let mut fetched = fetch(claimed_ids).await;
+                for (pair_id, claim) in claimed {
+                    let price =
+                        fetched
+                            .remove(&pair_id)
+                            .unwrap_or(Err(MonitoringError::Api(format!(
+                                "No reference price returned for pair {}",
+                                pair_id
+                            ))));
+                    if let Ok(price) = &price {
+                        // Only the claiming caller sets the price
+                        let _ = claim.0.price.set(price.clone());
+                    }
+                    results.insert(pair_id, price);
+                }
+            }
+
+            // Retry the prices that the other callers failed to fetch
+            futures::future::join_all(waiting.iter().map(|(_, entry)| entry.wait())).await;
+            pending = waiting;
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl<P: ReferencePriceProvider> ReferencePriceProvider for CachedProvider<P> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn reference_id(&self, pair_id: &str) -> Result<String, MonitoringError> {
+        self.inner.reference_id(pair_id)
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let pair_ids = [pair_id.to_string()];
+        self.historical_prices(&pair_ids, timestamp)
+            .await
+            .remove(pair_id)
+            .unwrap_or(Err(MonitoringError::Api(format!(
+                "No reference price returned for pair {}",
+                pair_id
+            ))))
+    }
+
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        let pair_ids = [pair_id.to_string()];
+        self.current_prices(&pair_ids)
+            .await
+            .remove(pair_id)
+            .unwrap_or(Err(MonitoringError::Api(format!(
+                "No reference price returned for pair {}",
+                pair_id
+            ))))
+    }
+
+    async fn historical_prices(&self, pair_ids: &[String], timestamp: u64) -> ReferencePrices {
+        self.prices(pair_ids, timestamp, false, |pair_ids| async move {
+            match pair_ids.as_slice() {
+                [pair_id] => [(
+                    pair_id.clone(),
+                    self.inner.historical_price(pair_id, timestamp).await,
+                )]
+                .into(),
+                _ => self.inner.historical_prices(&pair_ids, timestamp).await,
+            }
+        })
+        .await
+    }
+
+    async fn current_prices(&self, pair_ids: &[String]) -> ReferencePrices {
+        let now = chrono::Utc::now().timestamp() as u64;
+        self.prices(pair_ids, now, true, |pair_ids| async move {
+            match pair_ids.as_slice() {
+                [pair_id] => [(pair_id.clone(), self.inner.current_price(pair_id).await)].into(),
+                _ => self.inner.current_prices(&pair_ids).await,
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use super::{coingecko_id, ReferencePrice, ReferencePriceProvider};
+use crate::error::MonitoringError;
+
+const COINGECKO_BASE_URL: &str = "https://api.coingecko.com/api/v3";
+const COINGECKO_PRO_BASE_URL: &str = "https://pro-api.coingecko.com/api/v3";
+
+/// Half-width of the window (in seconds) queried around a timestamp when
+/// looking up an historical price. Coingecko returns 5 minutes granularity
+/// for ranges within the last day.
+const HISTORICAL_WINDOW: u64 = 30 * 60;
+
+/// Data Transfer Object for Coingecko `market_chart/range` API
+/// e.g
+/// {
+///   "prices": [[1702677600000, 42220.12], [1702677900000, 42231.5]],
+///   ...
+/// }
+#[derive(serde::Deserialize, Debug)]
+pub struct MarketChartDTO {
+    prices: Vec<(u64, f64)>,
+}
+
+/// Data Transfer Object for Coingecko `simple/price` API
+/// e.g
+/// {
+///   "bitcoin": { "usd": 42220.12 }
+/// }
+pub type SimplePriceDTO = HashMap<String, HashMap<String, f64>>;
+
+/// Reference prices from the Coingecko API.
+pub struct CoingeckoProvider {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl CoingeckoProvider {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+
+    /// Builds the provider using the optional `COINGECKO_API_KEY` env variable.
+    /// When set, requests are sent to the pro API.
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("COINGECKO_API_KEY").ok())
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T, MonitoringError> {
+        let mut headers = HeaderMap::new();
+        let base_url = match &self.api_key {
+            Some(api_key) => {
+                headers.insert(
+                    HeaderName::from_static("x-cg-pro-api-key"),
+                    HeaderValue::from_str(api_key)
+                        .map_err(|e| MonitoringError::Api(e.to_string()))?,
+                );
+                COINGECKO_PRO_BASE_URL
+            }
+            None => COINGECKO_BASE_URL,
+        };
+
+        let response = self
+            .client
+            .get(format!("{base_url}/{path}"))
+            .headers(headers)
+            .send()
+            .await
+            .map_err(|e| MonitoringError::Api(e.to_string()))?;
+
+        match response.status() {
+            reqwest::StatusCode::OK => response.json::<T>().await.map_err(|e| {
+                MonitoringError::Api(format!(
+                    "Failed to convert to DTO object, got error {:?}",
+                    e.to_string()
+                ))
+            }),
+            other => Err(MonitoringError::Api(format!(
+                "Unexpected response status from coingecko: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl ReferencePriceProvider for CoingeckoProvider {
+    fn name(&self) -> &'static str {
+        "coingecko"
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let coingecko_id = coingecko_id(pair_id)?;
+
+        let chart: MarketChartDTO = self
+            .get(&format!(
+                "coins/{coingecko_id}/market_chart/range?vs_currency=usd&from={from}&to={to}",
+                from = timestamp.saturating_sub(HISTORICAL_WINDOW),
+                to = timestamp + HISTORICAL_WINDOW,
+            ))
+            .await?;
+
+        // Take the closest price to the requested timestamp
+        let timestamp_ms = timestamp * 1000;
+        let (_, price) = chart
+            .prices
+            .into_iter()
+            .min_by_key(|(time, _)| time.abs_diff(timestamp_ms))
+            .ok_or(MonitoringError::Api(format!(
+                "Failed to get coingecko price for id {:?}",
+                coingecko_id
+            )))?;
+
+        Ok(ReferencePrice::new(price, self.name()))
+    }
+
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        let coingecko_id = coingecko_id(pair_id)?;
+
+        let prices: SimplePriceDTO = self
+            .get(&format!(
+                "simple/price?ids={coingecko_id}&vs_currencies=usd"
+            ))
+            .await?;
+
+        let price = *prices
+            .get(&coingecko_id)
+            .and_then(|price| price.get("usd"))
+            .ok_or(MonitoringError::Api(format!(
+                "Failed to get coingecko price for id {:?}",
+                coingecko_id
+            )))?;
+
+        Ok(ReferencePrice::new(price, self.name()))
+    }
+}
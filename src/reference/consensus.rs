@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+
+use super::{ReferencePrice, ReferencePriceProvider, ReferencePrices};
+use crate::error::MonitoringError;
+
+/// Consensus between several reference prices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consensus {
+    /// Median of the references agreeing with the median of all references.
+    pub price: f64,
+    /// Relative spread between the highest and lowest reference.
+    pub spread: f64,
+    /// Number of references within the allowed deviation from the median.
+    pub agreeing: usize,
+}
+
+/// Queries several reference providers and returns the median of the
+/// references that agree with each other.
+pub struct ConsensusProvider {
+    providers: Vec<Box<dyn ReferencePriceProvider>>,
+    /// Minimum number of agreeing references required to compute a consensus.
+    min_agreeing: usize,
+    /// Maximum relative deviation from the median for a reference to agree.
+    max_deviation: f64,
+}
+
+impl ConsensusProvider {
+    pub fn new(
+        providers: Vec<Box<dyn ReferencePriceProvider>>,
+        min_agreeing: usize,
+        max_deviation: f64,
+    ) -> Self {
+        Self {
+            providers,
+            min_agreeing,
+            max_deviation,
+        }
+    }
+
+    fn resolve(
+        &self,
+        pair_id: &str,
+        results: Vec<Result<ReferencePrice, MonitoringError>>,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let prices = results
+            .into_iter()
+            .zip(&self.providers)
+            .filter_map(|(result, provider)| match result {
+                Ok(reference) => Some(reference.price),
+                Err(e) => {
+                    log::warn!(
+                        "[REFERENCE] {} failed for pair {}: {e}",
+                        provider.name(),
+                        pair_id
+                    );
+                    None
+                }
+            })
+            .collect::<Vec<f64>>();
+
+        let consensus = consensus(&prices, self.max_deviation).ok_or(MonitoringError::Api(
+            format!("All reference providers failed for pair {}", pair_id),
+        ))?;
+
+        if consensus.agreeing < self.min_agreeing {
+            return Err(MonitoringError::NoConsensus {
+                agreeing: consensus.agreeing,
+                spread: consensus.spread,
+            });
+        }
+
+        Ok(ReferencePrice {
+            price: consensus.price,
+            reference: self.name(),
+            spread: Some(consensus.spread),
+        })
+    }
+
+    /// Resolves the consensus of each pair from the batched prices of every provider.
+    fn resolve_batch(
+        &self,
+        pair_ids: &[String],
+        mut prices: Vec<ReferencePrices>,
+    ) -> ReferencePrices {
+        pair_ids
+            .iter()
+            .map(|pair_id| {
+                let results = prices
+                    .iter_mut()
+                    .map(|provider_prices| {
+                        provider_prices
+                            .remove(pair_id)
+                            .unwrap_or(Err(MonitoringError::Api(format!(
+                                "No reference price returned for pair {}",
+                                pair_id
+                            ))))
+                    })
+                    .collect();
+                (pair_id.clone(), self.resolve(pair_id, results))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ReferencePriceProvider for ConsensusProvider {
+    fn name(&self) -> &'static str {
+        "consensus"
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let results = futures::future::join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.historical_price(pair_id, timestamp)),
+        )
+        .await;
+
+        self.resolve(pair_id, results)
+    }
+
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        let results = futures::future::join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.current_price(pair_id)),
+        )
+        .await;
+
+        self.resolve(pair_id, results)
+    }
+
+    async fn historical_prices(&self, pair_ids: &[String], timestamp: u64) -> ReferencePrices {
+        let prices = futures::future::join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.historical_prices(pair_ids, timestamp)),
+        )
+        .await;
+
+        self.resolve_batch(pair_ids, prices)
+    }
+
+    async fn current_prices(&self, pair_ids: &[String]) -> ReferencePrices {
+        let prices = futures::future::join_all(
+            self.providers
+                .iter()
+                .map(|provider| provider.current_prices(pair_ids)),
+        )
+        .await;
+
+        self.resolve_batch(pair_ids, prices)
+    }
+}
+
+/// Computes the consensus between the given prices.
+/// A price agrees if it deviates from the median of all prices by at most `max_deviation`,
+/// the consensus price is then the median of the agreeing prices.
+/// Returns None if no prices are given.
+pub fn consensus(prices: &[f64], max_deviation: f64) -> Option<Consensus> {
+    let all_median = median(prices)?;
+
+    let agreeing = prices
+        .iter()
+        .copied()
+        .filter(|price| ((price - all_median) / all_median).abs() <= max_deviation)
+        .collect::<Vec<f64>>();
+
+    let max = prices.iter().copied().fold(f64::MIN, f64::max);
+    let min = prices.iter().copied().fold(f64::MAX, f64::min);
+
+    Some(Consensus {
+        price: median(&agreeing).unwrap_or(all_median),
+        spread: (max - min) / all_median,
+        agreeing: agreeing.len(),
+    })
+}
+
+fn median(prices: &[f64]) -> Option<f64> {
+    let mut sorted = prices.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
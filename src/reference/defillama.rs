@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::{coingecko_id, ReferencePrice, ReferencePriceProvider, ReferencePrices};
+use crate::error::MonitoringError;
+
+const DEFILLAMA_BASE_URL: &str = "https://coins.llama.fi/prices";
+
+/// Data Transfer Object for Defillama API
+/// e.g
+///{
+//   "coins": {
+//     "coingecko:bitcoin": {
+//       "price": 42220,
+//       "symbol": "BTC",
+//       "timestamp": 1702677632,
+//       "confidence": 0.99
+//     }
+//   }
+// }
+#[derive(serde::Deserialize, Debug)]
+pub struct CoinPricesDTO {
+    coins: HashMap<String, CoinPriceDTO>,
+}
+
+#[allow(unused)]
+#[derive(serde::Deserialize, Debug)]
+pub struct CoinPriceDTO {
+    price: f64,
+    symbol: String,
+    timestamp: u64,
+    confidence: f64,
+}
+
+impl CoinPricesDTO {
+    pub fn get_coins(&self) -> &HashMap<String, CoinPriceDTO> {
+        &self.coins
+    }
+}
+impl CoinPriceDTO {
+    pub fn get_price(&self) -> f64 {
+        self.price
+    }
+}
+
+/// Reference prices from the DefiLlama coins API.
+pub struct DefillamaProvider {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl DefillamaProvider {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+
+    /// Builds the provider using the optional `DEFILLAMA_API_KEY` env variable.
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("DEFILLAMA_API_KEY").ok())
+    }
+
+    /// Fetches the prices of the given coingecko ids from the given endpoint
+    /// e.g `historical/1702677632` or `current`
+    /// Several coins are fetched in a single request.
+    async fn fetch_prices(
+        &self,
+        endpoint: &str,
+        coingecko_ids: &[&str],
+    ) -> Result<CoinPricesDTO, MonitoringError> {
+        let coins = coingecko_ids
+            .iter()
+            .map(|id| format!("coingecko:{}", id))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        let request_url = match &self.api_key {
+            Some(api_key) => format!(
+                "{DEFILLAMA_BASE_URL}/{endpoint}/{coins}?apikey={apikey}",
+                apikey = api_key
+            ),
+            None => format!("{DEFILLAMA_BASE_URL}/{endpoint}/{coins}"),
+        };
+
+        let response = self
+            .client
+            .get(&request_url)
+            .send()
+            .await
+            .map_err(|e| MonitoringError::Api(e.to_string()))?;
+
+        response.json().await.map_err(|e| {
+            MonitoringError::Api(format!(
+                "Failed to convert to DTO object, got error {:?}",
+                e.to_string()
+            ))
+        })
+    }
+
+    fn price_of(
+        &self,
+        prices: &CoinPricesDTO,
+        coingecko_id: &str,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let price = prices
+            .get_coins()
+            .get(&format!("coingecko:{}", coingecko_id))
+            .ok_or(MonitoringError::Api(format!(
+                "Failed to get coingecko price for id {:?}",
+                coingecko_id
+            )))?
+            .get_price();
+
+        Ok(ReferencePrice::new(price, self.name()))
+    }
+
+    /// Fetches the prices of all the given pairs in a single request.
+    async fn batch_prices(&self, endpoint: &str, pair_ids: &[String]) -> ReferencePrices {
+        let mut results = ReferencePrices::new();
+
+        let mut ids = Vec::new();
+        for pair_id in pair_ids {
+            match coingecko_id(pair_id) {
+                Ok(id) => ids.push((pair_id, id)),
+                Err(e) => {
+                    results.insert(pair_id.clone(), Err(e));
+                }
+            }
+        }
+        if ids.is_empty() {
+            return results;
+        }
+
+        let coingecko_ids = ids.iter().map(|(_, id)| id.as_str()).collect::<Vec<&str>>();
+        match self.fetch_prices(endpoint, &coingecko_ids).await {
+            Ok(prices) => {
+                for (pair_id, id) in ids {
+                    results.insert(pair_id.clone(), self.price_of(&prices, &id));
+                }
+            }
+            Err(e) => {
+                for (pair_id, _) in ids {
+                    results.insert(pair_id.clone(), Err(MonitoringError::Api(e.to_string())));
+                }
+            }
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl ReferencePriceProvider for DefillamaProvider {
+    fn name(&self) -> &'static str {
+        "defillama"
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let coingecko_id = coingecko_id(pair_id)?;
+        let prices = self
+            .fetch_prices(&format!("historical/{timestamp}"), &[&coingecko_id])
+            .await?;
+        self.price_of(&prices, &coingecko_id)
+    }
+
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        let coingecko_id = coingecko_id(pair_id)?;
+        let prices = self.fetch_prices("current", &[&coingecko_id]).await?;
+        self.price_of(&prices, &coingecko_id)
+    }
+
+    async fn historical_prices(&self, pair_ids: &[String], timestamp: u64) -> ReferencePrices {
+        self.batch_prices(&format!("historical/{timestamp}"), pair_ids)
+            .await
+    }
+
+    async fn current_prices(&self, pair_ids: &[String]) -> ReferencePrices {
+        self.batch_prices("current", pair_ids).await
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::{ReferencePrice, ReferencePriceProvider, ReferencePrices};
+use crate::error::MonitoringError;
+
+/// Ordered chain of reference providers.
+/// Each provider is queried in turn until one of them returns a price.
+pub struct FallbackProvider {
+    providers: Vec<Box<dyn ReferencePriceProvider>>,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<Box<dyn ReferencePriceProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Queries the providers in turn with the pairs still missing a price,
+    /// `fetch` performing the batched request of a provider.
+    async fn batch<'a, F>(&'a self, pair_ids: &[String], fetch: F) -> ReferencePrices
+    where
+        F: Fn(
+            &'a dyn ReferencePriceProvider,
+            Vec<String>,
+        ) -> futures::future::BoxFuture<'a, ReferencePrices>,
+    {
+        let mut results = ReferencePrices::new();
+        let mut errors: HashMap<String, Vec<String>> = HashMap::new();
+        let mut missing = pair_ids.to_vec();
+
+        for provider in &self.providers {
+            if missing.is_empty() {
+                break;
+            }
+
+            let prices = fetch(provider.as_ref(), missing.clone()).await;
+            missing.retain(|pair_id| match prices.get(pair_id) {
+                Some(Ok(price)) => {
+                    results.insert(pair_id.clone(), Ok(price.clone()));
+                    false
+                }
+                Some(Err(e)) => {
+                    log::warn!(
+                        "[REFERENCE] {} failed for pair {}: {e}",
+                        provider.name(),
+                        pair_id
+                    );
+                    errors
+                        .entry(pair_id.clone())
+                        .or_default()
+                        .push(format!("{}: {e}", provider.name()));
+                    true
+                }
+                None => true,
+            });
+        }
+
+        for pair_id in missing {
+            let errors = errors.remove(&pair_id).unwrap_or_default();
+            results.insert(
+                pair_id.clone(),
+                Err(MonitoringError::Api(format!(
+                    "All reference providers failed for pair {}: [{}]",
+                    pair_id,
+                    errors.join(", ")
+                ))),
+            );
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl ReferencePriceProvider for FallbackProvider {
+    fn name(&self) -> &'static str {
+        "fallback"
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        let mut errors = Vec::new();
+        for provider in &self.providers {
+            match provider.historical_price(pair_id, timestamp).await {
+                Ok(price) => return Ok(price),
+                Err(e) => {
+                    log::warn!(
+                        "[REFERENCE] {} failed for pair {}: {e}",
+                        provider.name(),
+                        pair_id
+                    );
+                    errors.push(format!("{}: {e}", provider.name()));
+                }
+            }
+        }
+
+        Err(MonitoringError::Api(format!(
+            "All reference providers failed for pair {}: [{}]",
+            pair_id,
+            errors.join(", ")
+        )))
+    }
+
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        let mut errors = Vec::new();
+        for provider in &self.providers {
+            match provider.current_price(pair_id).await {
+                Ok(price) => return Ok(price),
+                Err(e) => {
+                    log::warn!(
+                        "[REFERENCE] {} failed for pair {}: {e}",
+                        provider.name(),
+                        pair_id
+                    );
+                    errors.push(format!("{}: {e}", provider.name()));
+                }
+            }
+        }
+
+        Err(MonitoringError::Api(format!(
+            "All reference providers failed for pair {}: [{}]",
+            pair_id,
+            errors.join(", ")
+        )))
+    }
+
+    async fn historical_prices(&self, pair_ids: &[String], timestamp: u64) -> ReferencePrices {
+        self.batch(pair_ids, |provider, pair_ids| {
+            Box::pin(async move { provider.historical_prices(&pair_ids, timestamp).await })
+        })
+        .await
+    }
+
+    async fn current_prices(&self, pair_ids: &[String]) -> ReferencePrices {
+        self.batch(pair_ids, |provider, pair_ids| {
+            Box::pin(async move { provider.current_prices(&pair_ids).await })
+        })
+        .await
+    }
+}
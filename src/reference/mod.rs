@@ -0,0 +1,243 @@
+pub mod binance;
+pub mod cache;
+pub mod coingecko;
+pub mod consensus;
+pub mod defillama;
+pub mod fallback;
+
+pub use binance::BinanceFuturesProvider;
+pub use cache::CachedProvider;
+pub use coingecko::CoingeckoProvider;
+pub use consensus::ConsensusProvider;
+pub use defillama::DefillamaProvider;
+pub use fallback::FallbackProvider;
+
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+
+use crate::{
+    config::{DataType, ReferenceSettings, CONFIG},
+    constants::COINGECKO_IDS,
+    error::MonitoringError,
+};
+
+/// Names of all the references a price can be taken from.
+pub const REFERENCES: [&str; 4] = ["defillama", "coingecko", "consensus", "binance"];
+
+/// A price returned by a reference provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferencePrice {
+    pub price: f64,
+    /// Name of the reference the price was taken from.
+    pub reference: &'static str,
+    /// Relative spread between the references, when several were queried.
+    pub spread: Option<f64>,
+}
+
+impl ReferencePrice {
+    pub fn new(price: f64, reference: &'static str) -> Self {
+        Self {
+            price,
+            reference,
+            spread: None,
+        }
+    }
+}
+
+/// Reference prices of several pairs, indexed by pair id.
+pub type ReferencePrices = HashMap<String, Result<ReferencePrice, MonitoringError>>;
+
+/// Reference prices fetched in batch at the start of a tick, see `prefetch_reference_prices`.
+#[derive(Debug, Default)]
+pub struct PrefetchedPrices {
+    /// Timestamp the prices were fetched at. (in seconds)
+    pub timestamp: u64,
+    /// Duration of the bucket of `timestamp` the prices are used for, none if 0. (in seconds)
+    pub bucket: u64,
+    pub prices: ReferencePrices,
+}
+
+impl PrefetchedPrices {
+    /// Prices fetched at the given timestamp, used for the timestamps of its cache bucket.
+    pub fn new(timestamp: u64, prices: ReferencePrices) -> Self {
+        Self {
+            timestamp,
+            bucket: cache_bucket().as_secs(),
+            prices,
+        }
+    }
+
+    /// Returns the prefetched price of the pair, if the given timestamp (in seconds)
+    /// is in the bucket the prices were fetched for.
+    pub fn get(&self, pair_id: &str, timestamp: u64) -> Option<&ReferencePrice> {
+        let same_bucket =
+            self.bucket > 0 && timestamp / self.bucket == self.timestamp / self.bucket;
+        match self.prices.get(pair_id) {
+            Some(Ok(reference)) if same_bucket => Some(reference),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the price of the pair at the given timestamp (in seconds), from the prices
+/// fetched in batch for the tick when the timestamp is in their bucket.
+pub async fn prefetched_price(
+    provider: &dyn ReferencePriceProvider,
+    prefetched: &PrefetchedPrices,
+    pair_id: &str,
+    timestamp: u64,
+) -> Result<ReferencePrice, MonitoringError> {
+    match prefetched.get(pair_id, timestamp) {
+        Some(reference) => Ok(reference.clone()),
+        None => provider.historical_price(pair_id, timestamp).await,
+    }
+}
+
+/// A trusted off-chain source of prices used as a reference to compute
+/// the deviation of the oracle prices.
+#[async_trait]
+pub trait ReferencePriceProvider: Send + Sync {
+    /// Name of the reference, e.g `defillama`.
+    fn name(&self) -> &'static str;
+
+    /// Returns the id of the pair on the reference, used as cache key.
+    fn reference_id(&self, pair_id: &str) -> Result<String, MonitoringError> {
+        coingecko_id(pair_id)
+    }
+
+    /// Returns the price of the pair at the given timestamp (in seconds).
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError>;
+
+    /// Returns the latest price of the pair.
+    async fn current_price(&self, pair_id: &str) -> Result<ReferencePrice, MonitoringError>;
+
+    /// Returns the prices of several pairs at the given timestamp (in seconds).
+    /// Providers supporting batched requests should override it.
+    async fn historical_prices(&self, pair_ids: &[String], timestamp: u64) -> ReferencePrices {
+        futures::future::join_all(pair_ids.iter().map(|pair_id| async move {
+            (
+                pair_id.clone(),
+                self.historical_price(pair_id, timestamp).await,
+            )
+        }))
+        .await
+        .into_iter()
+        .collect()
+    }
+
+    /// Returns the latest prices of several pairs.
+    /// Providers supporting batched requests should override it.
+    async fn current_prices(&self, pair_ids: &[String]) -> ReferencePrices {
+        futures::future::join_all(
+            pair_ids
+                .iter()
+                .map(|pair_id| async move { (pair_id.clone(), self.current_price(pair_id).await) }),
+        )
+        .await
+        .into_iter()
+        .collect()
+    }
+}
+
+lazy_static! {
+    /// Invalid settings are reported when loading the configuration, the defaults are used instead.
+    static ref REFERENCE_SETTINGS: ReferenceSettings =
+        ReferenceSettings::from_env().unwrap_or_else(|report| {
+            log::error!("[REFERENCE] Invalid reference settings, using the defaults\n{report}");
+            ReferenceSettings::default()
+        });
+    static ref REFERENCE_PROVIDER: Box<dyn ReferencePriceProvider> = init_reference_provider();
+    static ref FUTURE_REFERENCE_PROVIDER: CachedProvider<BinanceFuturesProvider> =
+        CachedProvider::new(BinanceFuturesProvider::new(), cache_bucket(), cache_ttl());
+}
+
+/// Returns the reference price provider shared by all the monitoring tasks.
+pub fn reference_provider() -> &'static dyn ReferencePriceProvider {
+    REFERENCE_PROVIDER.as_ref()
+}
+
+/// Returns the reference price provider of perpetual futures, shared by all the monitoring tasks.
+pub fn future_reference_provider() -> &'static dyn ReferencePriceProvider {
+    &*FUTURE_REFERENCE_PROVIDER
+}
+
+/// Returns the reference price provider of the given type of data.
+pub fn data_type_reference_provider(data_type: &DataType) -> &'static dyn ReferencePriceProvider {
+    match data_type {
+        DataType::Spot => reference_provider(),
+        DataType::Future => future_reference_provider(),
+    }
+}
+
+/// Builds the reference price provider from the environment variables.
+/// `REFERENCE_MODE` selects how the `REFERENCE_PROVIDERS` are combined:
+/// - `fallback` (default): the first provider returning a price is used.
+/// - `consensus`: all providers are queried and their median is used when
+///   at least `REFERENCE_MIN_AGREEING` of them are within `REFERENCE_MAX_DEVIATION` of it.
+fn init_reference_provider() -> Box<dyn ReferencePriceProvider> {
+    let providers = init_providers(&REFERENCE_SETTINGS.providers);
+
+    match std::env::var("REFERENCE_MODE")
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "consensus" => Box::new(ConsensusProvider::new(
+            providers,
+            REFERENCE_SETTINGS.min_agreeing,
+            REFERENCE_SETTINGS.max_deviation,
+        )),
+        _ => Box::new(FallbackProvider::new(providers)),
+    }
+}
+
+/// Builds the reference providers of the given names, in order, see `ReferenceSettings`.
+/// Every provider is wrapped in a cache configured by `REFERENCE_CACHE_BUCKET`
+/// and `REFERENCE_CACHE_TTL` (in seconds).
+pub fn init_providers(names: &[String]) -> Vec<Box<dyn ReferencePriceProvider>> {
+    let bucket = cache_bucket();
+    let ttl = cache_ttl();
+
+    names
+        .iter()
+        .filter_map(|name| match name.as_str() {
+            "defillama" => Some(Box::new(CachedProvider::new(
+                DefillamaProvider::from_env(),
+                bucket,
+                ttl,
+            )) as Box<dyn ReferencePriceProvider>),
+            "coingecko" => Some(Box::new(CachedProvider::new(
+                CoingeckoProvider::from_env(),
+                bucket,
+                ttl,
+            )) as _),
+            // Reported when loading the configuration
+            _ => None,
+        })
+        .collect()
+}
+
+fn cache_bucket() -> Duration {
+    Duration::from_secs(REFERENCE_SETTINGS.cache_bucket)
+}
+
+fn cache_ttl() -> Duration {
+    Duration::from_secs(REFERENCE_SETTINGS.cache_ttl)
+}
+
+/// Returns the coingecko id of the given pair from the configuration,
+/// or from the default `COINGECKO_IDS` if the configuration is not loaded yet.
+pub fn coingecko_id(pair_id: &str) -> Result<String, MonitoringError> {
+    let id = match CONFIG.get() {
+        Some(config) => config.load().reference_id(pair_id).map(str::to_string),
+        None => COINGECKO_IDS.get(pair_id).map(|id| id.to_string()),
+    };
+
+    id.ok_or(MonitoringError::MissingReferenceId(pair_id.to_string()))
+}
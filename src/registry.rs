@@ -0,0 +1,140 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+use crate::{
+    config::DataType,
+    constants::{
+        REGISTRY_PUBLISHER_ADDED, REGISTRY_PUBLISHER_REMOVED, REGISTRY_SOURCE_ADDED,
+        REGISTRY_SOURCE_REMOVED,
+    },
+};
+
+/// Publishers of the registry and sources of the pairs on the oracle, as read
+/// when building the config (before ignoring any publisher or source).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryState {
+    pub publishers: BTreeSet<String>,
+    pub sources: HashMap<(DataType, String), BTreeSet<String>>,
+}
+
+/// A change of the on-chain registry between two refreshes of the config.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RegistryChange {
+    PublisherAdded(String),
+    PublisherRemoved(String),
+    SourceAdded {
+        data_type: String,
+        pair: String,
+        source: String,
+    },
+    SourceRemoved {
+        data_type: String,
+        pair: String,
+        source: String,
+    },
+}
+
+impl fmt::Display for RegistryChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryChange::PublisherAdded(publisher) => {
+                write!(f, "Publisher {publisher} added to the registry")
+            }
+            RegistryChange::PublisherRemoved(publisher) => {
+                write!(f, "Publisher {publisher} removed from the registry")
+            }
+            RegistryChange::SourceAdded {
+                data_type,
+                pair,
+                source,
+            } => write!(f, "Source {source} added to the {data_type} pair {pair}"),
+            RegistryChange::SourceRemoved {
+                data_type,
+                pair,
+                source,
+            } => write!(
+                f,
+                "Source {source} removed from the {data_type} pair {pair}"
+            ),
+        }
+    }
+}
+
+impl RegistryState {
+    /// Returns the changes from `previous` to this state.
+    /// Only the pairs monitored in both states are compared, adding or removing
+    /// a pair from the config is not a change of the registry.
+    pub fn changes_since(&self, previous: &RegistryState) -> Vec<RegistryChange> {
+        let mut changes = Vec::new();
+
+        changes.extend(
+            self.publishers
+                .difference(&previous.publishers)
+                .map(|publisher| RegistryChange::PublisherAdded(publisher.clone())),
+        );
+        changes.extend(
+            previous
+                .publishers
+                .difference(&self.publishers)
+                .map(|publisher| RegistryChange::PublisherRemoved(publisher.clone())),
+        );
+
+        for ((data_type, pair), sources) in self.sources.iter() {
+            let Some(previous_sources) = previous.sources.get(&(data_type.clone(), pair.clone()))
+            else {
+                continue;
+            };
+            let data_type = data_type.to_string().to_ascii_lowercase();
+
+            changes.extend(sources.difference(previous_sources).map(|source| {
+                RegistryChange::SourceAdded {
+                    data_type: data_type.clone(),
+                    pair: pair.clone(),
+                    source: source.clone(),
+                }
+            }));
+            changes.extend(previous_sources.difference(sources).map(|source| {
+                RegistryChange::SourceRemoved {
+                    data_type: data_type.clone(),
+                    pair: pair.clone(),
+                    source: source.clone(),
+                }
+            }));
+        }
+
+        changes.sort();
+        changes
+    }
+}
+
+/// Logs the changes of the registry of a network and counts them in the metrics.
+pub fn report_changes(network: &str, changes: &[RegistryChange]) {
+    for change in changes {
+        log::warn!("[{network}][REGISTRY] {change}");
+
+        match change {
+            RegistryChange::PublisherAdded(publisher) => REGISTRY_PUBLISHER_ADDED
+                .with_label_values(&[network, publisher])
+                .inc(),
+            RegistryChange::PublisherRemoved(publisher) => REGISTRY_PUBLISHER_REMOVED
+                .with_label_values(&[network, publisher])
+                .inc(),
+            RegistryChange::SourceAdded {
+                data_type,
+                pair,
+                source,
+            } => REGISTRY_SOURCE_ADDED
+                .with_label_values(&[network, pair, source, data_type])
+                .inc(),
+            RegistryChange::SourceRemoved {
+                data_type,
+                pair,
+                source,
+            } => REGISTRY_SOURCE_REMOVED
+                .with_label_values(&[network, pair, source, data_type])
+                .inc(),
+        }
+    }
+}
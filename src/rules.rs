@@ -0,0 +1,210 @@
+use std::fmt::Write;
+
+use crate::config::{Check, ConfigInput, DataType, PairSettings};
+
+/// An alerting rule of a pair (or of a source of a pair).
+struct Rule {
+    alert: &'static str,
+    expr: String,
+    severity: &'static str,
+    summary: &'static str,
+    description: String,
+}
+
+/// Generates the Prometheus alerting rules of the configured pairs, with the
+/// thresholds of the config hard-coded in the expressions.
+/// Sources with their own thresholds get their own rules and are excluded from the pair ones.
+pub fn generate_rules(config_inputs: &[ConfigInput]) -> String {
+    let mut output = String::from(
+        "# Generated by `pragma-monitoring generate-rules` from the monitoring config.\ngroups:\n",
+    );
+
+    for config_input in config_inputs {
+        let pairs = [
+            (
+                DataType::Spot,
+                &config_input.spot_pairs,
+                &config_input.spot_settings,
+            ),
+            (
+                DataType::Future,
+                &config_input.future_pairs,
+                &config_input.future_settings,
+            ),
+        ];
+
+        let rules = pairs
+            .into_iter()
+            .flat_map(|(data_type, pairs, settings)| {
+                pairs.iter().flat_map(move |pair| {
+                    let default_settings = PairSettings::default();
+                    let settings = settings.get(pair).unwrap_or(&default_settings);
+                    pair_rules(config_input.network.as_str(), &data_type, pair, settings)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let _ = writeln!(
+            output,
+            "  - name: Thresholds {}\n    rules:",
+            config_input.network
+        );
+        for rule in rules {
+            let _ = write!(
+                output,
+                "      - alert: {}\n        expr: {}\n        for: 5m\n        labels:\n          severity: {}\n        annotations:\n          summary: {}\n          description: {}\n",
+                rule.alert,
+                yaml_string(&rule.expr),
+                rule.severity,
+                yaml_string(rule.summary),
+                yaml_string(&rule.description),
+            );
+        }
+    }
+
+    output
+}
+
+fn pair_rules(
+    network: &str,
+    data_type: &DataType,
+    pair: &str,
+    settings: &PairSettings,
+) -> Vec<Rule> {
+    let type_label = data_type.to_string().to_ascii_lowercase();
+    let thresholds = settings.thresholds(None);
+    let selector = format!(
+        "network=\"{}\", pair=\"{}\", type=\"{}\"",
+        label_value(network),
+        label_value(pair),
+        type_label
+    );
+
+    // Sources with their own thresholds are excluded from the rules of the pair
+    let mut sources = settings.sources.keys().collect::<Vec<_>>();
+    sources.sort();
+    let sources_selectors = std::iter::once((
+        match sources.is_empty() {
+            true => selector.clone(),
+            false => format!(
+                "{selector}, source!~\"{}\"",
+                label_value(
+                    &sources
+                        .iter()
+                        .map(|source| regex_escape(source))
+                        .collect::<Vec<_>>()
+                        .join("|")
+                )
+            ),
+        },
+        thresholds.clone(),
+    ))
+    .chain(sources.iter().map(|source| {
+        (
+            format!("{selector}, source=\"{}\"", label_value(source)),
+            settings.thresholds(Some(source)),
+        )
+    }))
+    .collect::<Vec<_>>();
+
+    let mut rules = Vec::new();
+    for (selector, source_thresholds) in &sources_selectors {
+        if settings.is_enabled(Check::PriceDeviation) {
+            rules.push(Rule {
+                alert: "WrongPrice",
+                expr: format!(
+                    "abs(price_deviation{{{selector}}}) > {}",
+                    source_thresholds.price_deviation
+                ),
+                severity: "warning",
+                summary: "Price deviation is too high",
+                description: format!(
+                    "The price deviation of {pair} from {{{{ $labels.source }}}} has exceeded {}%.",
+                    percent(source_thresholds.price_deviation)
+                ),
+            });
+        }
+        if settings.is_enabled(Check::SourceDeviation) {
+            rules.push(Rule {
+                alert: "SourceDeviation",
+                expr: format!(
+                    "abs(price_deviation_source{{{selector}}}) > {}",
+                    source_thresholds.source_deviation
+                ),
+                severity: "warning",
+                summary: "Source deviation is too high",
+                description: format!(
+                    "The source deviation of {pair} from {{{{ $labels.source }}}} has exceeded {}%.",
+                    percent(source_thresholds.source_deviation)
+                ),
+            });
+        }
+    }
+
+    if settings.is_enabled(Check::OnOffDeviation) {
+        rules.push(Rule {
+            alert: "PriceDeviationTooHigh",
+            expr: format!(
+                "abs(on_off_price_deviation{{{selector}}}) > {}",
+                thresholds.on_off_deviation
+            ),
+            severity: "critical",
+            summary: "Price deviation is too high",
+            description: format!(
+                "The on-chain price of {pair} from the reference price has exceeded {}%.",
+                percent(thresholds.on_off_deviation)
+            ),
+        });
+    }
+    rules.push(Rule {
+        alert: "PairTimeSinceLastUpdateTooHigh",
+        expr: format!(
+            "time_since_last_update_pair_id{{{selector}}} > {}",
+            thresholds.time_since_last_update
+        ),
+        severity: "warning",
+        summary: "Time since the last update is too high",
+        description: format!(
+            "The time since the last update of {pair} has exceeded {} seconds.",
+            thresholds.time_since_last_update
+        ),
+    });
+    rules.push(Rule {
+        alert: "TooFewSources",
+        expr: format!("num_sources{{{selector}}} < {}", thresholds.num_sources),
+        severity: "critical",
+        summary: "Too few sources",
+        description: format!(
+            "The number of sources for {pair} has fallen below {}.",
+            thresholds.num_sources
+        ),
+    });
+
+    rules
+}
+
+/// Formats a deviation as a percentage, e.g 0.025 as 2.5.
+fn percent(deviation: f64) -> f64 {
+    (deviation * 10000.0).round() / 100.0
+}
+
+/// Escapes a PromQL label value.
+fn label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes the regex special characters of a label value.
+fn regex_escape(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(|c| match "\\.+*?()|[]{}^$".contains(c) {
+            true => vec!['\\', c],
+            false => vec![c],
+        })
+        .collect()
+}
+
+/// Quotes a YAML string.
+fn yaml_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use prometheus::proto::{MetricFamily, MetricType};
+use serde::Serialize;
+
+/// Output format of the snapshot.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+/// A value computed by the checks, i.e a sample of the exported metrics.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sample {
+    pub metric: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// Values computed by a single run of every check, along with the errors of the checks.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Snapshot {
+    pub samples: Vec<Sample>,
+    pub errors: Vec<String>,
+}
+
+impl Snapshot {
+    /// Builds the snapshot from the gathered metrics, only gauges and counters are kept.
+    pub fn new(metric_families: &[MetricFamily], errors: Vec<String>) -> Self {
+        let mut samples = metric_families
+            .iter()
+            .flat_map(|family| {
+                family.get_metric().iter().filter_map(|metric| {
+                    let value = match family.get_field_type() {
+                        MetricType::GAUGE => metric.get_gauge().get_value(),
+                        MetricType::COUNTER => metric.get_counter().get_value(),
+                        _ => return None,
+                    };
+                    let labels = metric
+                        .get_label()
+                        .iter()
+                        .map(|label| (label.get_name().to_string(), label.get_value().to_string()))
+                        .collect();
+                    Some(Sample {
+                        metric: family.get_name().to_string(),
+                        labels,
+                        value,
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        samples.sort_by(|a, b| (&a.metric, &a.labels).cmp(&(&b.metric, &b.labels)));
+
+        Self { samples, errors }
+    }
+
+    pub fn render(&self, format: SnapshotFormat) -> String {
+        match format {
+            SnapshotFormat::Table => self.to_table(),
+            SnapshotFormat::Json => {
+                serde_json::to_string_pretty(self).expect("Failed to serialize snapshot")
+            }
+        }
+    }
+
+    fn to_table(&self) -> String {
+        let rows = self
+            .samples
+            .iter()
+            .map(|sample| {
+                let labels = sample
+                    .labels
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                [sample.metric.clone(), labels, sample.value.to_string()]
+            })
+            .collect::<Vec<_>>();
+
+        let header = ["METRIC", "LABELS", "VALUE"].map(str::to_string);
+        let widths = std::iter::once(&header)
+            .chain(&rows)
+            .fold([0; 3], |mut widths, row| {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.len());
+                }
+                widths
+            });
+
+        let mut table = std::iter::once(&header)
+            .chain(&rows)
+            .map(|[metric, labels, value]| {
+                format!(
+                    "{metric:<metric_width$}  {labels:<labels_width$}  {value}",
+                    metric_width = widths[0],
+                    labels_width = widths[1],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if !self.errors.is_empty() {
+            table.push(format!("\nErrors ({}):", self.errors.len()));
+            table.extend(self.errors.iter().map(|e| format!("  {e}")));
+        }
+        table.join("\n")
+    }
+}
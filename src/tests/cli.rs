@@ -0,0 +1,26 @@
+use clap::Parser;
+
+use crate::cli::{Cli, Command};
+
+#[test]
+fn parses_subcommands() {
+    let cli = Cli::try_parse_from(["pragma-monitoring"]).unwrap();
+    assert!(matches!(cli.command.unwrap_or_default(), Command::Serve));
+
+    let cli = Cli::try_parse_from(["pragma-monitoring", "check-config"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::CheckConfig {
+            allow_warnings: false
+        })
+    ));
+
+    let cli =
+        Cli::try_parse_from(["pragma-monitoring", "check-config", "--allow-warnings"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::CheckConfig {
+            allow_warnings: true
+        })
+    ));
+}
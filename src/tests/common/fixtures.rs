@@ -1,12 +1,13 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use arc_swap::Guard;
 use deadpool::managed::Pool;
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
 use rstest::fixture;
-use starknet::core::types::Felt;
+use starknet::core::{chain_id, types::Felt};
 
-use crate::config::{config_force_init, get_config, Config, ConfigInput, NetworkName};
+use crate::config::{
+    config_force_init, get_config, Config, ConfigInput, NetworkName, SyncingPolicy,
+};
 
 #[fixture]
 pub fn database() -> Pool<AsyncDieselConnectionManager<diesel_async::AsyncPgConnection>> {
@@ -20,15 +21,31 @@ pub fn database() -> Pool<AsyncDieselConnectionManager<diesel_async::AsyncPgConn
 }
 
 #[fixture]
-pub async fn test_config() -> Guard<Arc<Config>> {
+pub async fn test_config() -> Arc<Config> {
     config_force_init(ConfigInput {
-        network: NetworkName::Testnet,
+        network: NetworkName::new(NetworkName::TESTNET),
+        rpc_url: std::env::var("RPC_URL").expect("RPC_URL must be set"),
+        chain_id: Some(chain_id::SEPOLIA),
+        table_prefix: "".to_string(),
+        api_url: None,
+        sequencer_url: None,
+        vrf_table: None,
+        syncing_policy: SyncingPolicy::default(),
         oracle_address: Felt::from_hex_unchecked(
             "0x06df335982dddce41008e4c03f2546fa27276567b5274c7d0c1262f3c2b5d167",
         ),
         spot_pairs: vec!["ETH/USD".to_string(), "BTC/USD".to_string()],
         future_pairs: vec!["ETH/USD".to_string(), "BTC/USD".to_string()],
+        reference_ids: HashMap::new(),
+        spot_settings: HashMap::new(),
+        future_settings: HashMap::new(),
+        ignore_sources: vec![],
+        ignore_publishers: vec![],
     })
     .await;
-    get_config(None).await
+    get_config(None)
+        .await
+        .network(&NetworkName::new(NetworkName::TESTNET))
+        .cloned()
+        .unwrap()
 }
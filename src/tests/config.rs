@@ -0,0 +1,86 @@
+use crate::config::{Check, ConfigFile, NetworkName, SyncingPolicy};
+use crate::validation::ValidationReport;
+
+const CONFIG_FILE: &str = r#"
+enabled_networks = ["testnet"]
+
+[intervals]
+monitor = 10
+
+[networks.Testnet]
+rpc_url = "https://starknet-sepolia.public.blastapi.io"
+oracle_address = "0x06df335982dddce41008e4c03f2546fa27276567b5274c7d0c1262f3c2b5d167"
+ignore_sources = ["BITSTAMP"]
+syncing_policy = "max_lag:20"
+
+[networks.Testnet.spot_pairs."BTC/USD"]
+reference_id = "bitcoin"
+thresholds = { price_deviation = 0.025 }
+
+[networks.Testnet.spot_pairs."ETH/USD"]
+
+[networks.Testnet.future_pairs."BTC/USD"]
+checks = ["source_deviation", "on_off_deviation"]
+"#;
+
+#[test]
+fn parses_config_file() {
+    let file = ConfigFile::parse(CONFIG_FILE).unwrap();
+
+    assert_eq!(file.enabled_networks, Some(vec!["testnet".to_string()]));
+    assert_eq!(file.intervals.monitor, 10);
+    assert_eq!(file.intervals.api, 30);
+    assert_eq!(file.intervals.checkpoints, 60);
+
+    let network = file.network("testnet");
+    assert_eq!(network.ignore_sources, vec!["BITSTAMP"]);
+    assert_eq!(network.syncing_policy, Some(SyncingPolicy::MaxLag(20)));
+    assert_eq!(network.spot_pairs.len(), 2);
+
+    let btc = &network.spot_pairs["BTC/USD"];
+    assert_eq!(btc.reference_id.as_deref(), Some("bitcoin"));
+    assert_eq!(btc.thresholds.price_deviation, Some(0.025));
+    assert!(btc.is_enabled(Check::PriceDeviation));
+
+    let btc_future = &network.future_pairs["BTC/USD"];
+    assert!(!btc_future.is_enabled(Check::PriceDeviation));
+    assert!(btc_future.is_enabled(Check::OnOffDeviation));
+
+    assert!(file.network("mainnet").spot_pairs.is_empty());
+}
+
+#[test]
+fn rejects_unknown_fields() {
+    assert!(ConfigFile::parse("[networks.testnet]\noracle = \"0x1\"").is_err());
+    assert!(
+        ConfigFile::parse("[networks.testnet.spot_pairs.\"BTC/USD\"]\nchecks = [\"foo\"]").is_err()
+    );
+    assert!(ConfigFile::parse("[networks.testnet]\nsyncing_policy = \"max_lag\"").is_err());
+}
+
+#[test]
+fn parses_example_config_file() {
+    let file = ConfigFile::parse(include_str!("../../config.example.toml")).unwrap();
+    assert!(!file.network("testnet").spot_pairs.is_empty());
+}
+
+#[test]
+fn normalizes_known_network_names() {
+    assert_eq!(NetworkName::new("mainnet").as_str(), "Mainnet");
+    assert_eq!(NetworkName::new("TESTNET").as_str(), "Testnet");
+    assert_eq!(NetworkName::new("katana").as_str(), "katana");
+}
+
+#[test]
+fn reports_fatal_issues_first() {
+    let mut report = ValidationReport::default();
+    report.warning(Some("Testnet"), "Spot pair FOO/USD", "no reference id");
+    report.fatal(Some("Testnet"), "RPC_URL", "failed to get the chain id");
+    assert!(report.is_fatal());
+    assert_eq!(
+        report.to_string(),
+        "Configuration report: 1 fatal error(s), 1 warning(s)\n  \
+         [FATAL][Testnet] RPC_URL: failed to get the chain id\n  \
+         [WARN][Testnet] Spot pair FOO/USD: no reference id"
+    );
+}
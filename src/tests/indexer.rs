@@ -0,0 +1,62 @@
+use crate::config::SyncingPolicy;
+use crate::processing::indexer::{tick_decision, TableStatus, TickDecision};
+
+#[test]
+fn tolerates_lag_measured_from_the_database() {
+    let status = |blocks_left, indexer_status| TableStatus {
+        blocks_left,
+        last_block_timestamp: None,
+        indexer_status,
+    };
+
+    assert!(status(Some(3), Some((1, None))).is_syncing());
+    assert!(!status(Some(0), Some((1, None))).is_syncing());
+    assert!(!status(Some(3), None).is_syncing());
+    assert!(status(Some(50), None).is_syncing());
+    // Unknown lag, e.g empty table
+    assert!(status(None, None).is_syncing());
+}
+
+#[test]
+fn applies_the_syncing_policy() {
+    let status = |blocks_left| TableStatus {
+        blocks_left,
+        last_block_timestamp: None,
+        indexer_status: Some((1, None)),
+    };
+    let synced = status(Some(0));
+    let lagging = status(Some(20));
+
+    for policy in [
+        SyncingPolicy::Skip,
+        SyncingPolicy::Run,
+        SyncingPolicy::MaxLag(50),
+    ] {
+        assert_eq!(tick_decision(policy, Some(&synced)), TickDecision::Run);
+    }
+
+    assert_eq!(
+        tick_decision(SyncingPolicy::Skip, Some(&lagging)),
+        TickDecision::Skip("syncing")
+    );
+    assert_eq!(
+        tick_decision(SyncingPolicy::Skip, None),
+        TickDecision::Skip("status_unavailable")
+    );
+    assert_eq!(
+        tick_decision(SyncingPolicy::Run, None),
+        TickDecision::RunUnsynced
+    );
+    assert_eq!(
+        tick_decision(SyncingPolicy::MaxLag(50), Some(&lagging)),
+        TickDecision::RunUnsynced
+    );
+    assert_eq!(
+        tick_decision(SyncingPolicy::MaxLag(10), Some(&lagging)),
+        TickDecision::Skip("syncing")
+    );
+    assert_eq!(
+        tick_decision(SyncingPolicy::MaxLag(50), Some(&status(None))),
+        TickDecision::Skip("syncing")
+    );
+}
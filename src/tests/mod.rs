@@ -1,4 +1,24 @@
 mod common;
 
+#[cfg(test)]
+mod cli;
+#[cfg(test)]
+mod config;
+#[cfg(test)]
+mod indexer;
 #[cfg(test)]
 mod monitoring;
+#[cfg(test)]
+mod normalize_price;
+#[cfg(test)]
+mod reference;
+#[cfg(test)]
+mod registry;
+#[cfg(test)]
+mod rules;
+#[cfg(test)]
+mod snapshot;
+#[cfg(test)]
+mod stale_metrics;
+#[cfg(test)]
+mod vrf;
@@ -1,14 +1,13 @@
 use std::sync::Arc;
 
 use crate::{
-    config::{Config, DataType},
+    config::{Config, DataType, NetworkName},
     monitor,
     tests::common::{
         fixtures::{database, test_config},
         utils::{publish_data, wait_for_expect},
     },
 };
-use arc_swap::Guard;
 use deadpool::managed::Pool;
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
 use rstest::rstest;
@@ -19,7 +18,7 @@ use tokio::sync::Mutex;
 #[ignore = "Blocked by #002"]
 async fn detects_publisher_down(
     database: Pool<AsyncDieselConnectionManager<diesel_async::AsyncPgConnection>>,
-    #[future] test_config: Guard<Arc<Config>>,
+    #[future] test_config: Arc<Config>,
 ) {
     let mut _conn = database.get().await.unwrap();
     let config = test_config.await;
@@ -30,7 +29,13 @@ async fn detects_publisher_down(
     // Spawn non-blocking monitor
     let monitor_handle = tokio::spawn(async move {
         let db = db_clone.lock().await;
-        monitor(db.clone(), false, &DataType::Spot).await;
+        monitor(
+            db.clone(),
+            NetworkName::new(NetworkName::TESTNET),
+            false,
+            &DataType::Spot,
+        )
+        .await;
     });
 
     // Publish a wrong price
@@ -0,0 +1,21 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+
+use crate::monitoring::normalize_price;
+
+#[test]
+fn normalizes_high_decimal_prices() {
+    // ETH/STRK has 18 decimals, 10^18 does not fit in a u32
+    let price = BigDecimal::from_str("5132000000000000000000").unwrap();
+    assert_eq!(normalize_price(&price, 18).unwrap(), 5132.0);
+
+    let price = BigDecimal::from_str("4222012345678").unwrap();
+    assert_eq!(normalize_price(&price, 8).unwrap(), 42220.12345678);
+}
+
+#[test]
+fn fails_to_normalize_out_of_range_prices() {
+    let price = BigDecimal::from_str("1e400").unwrap();
+    assert!(normalize_price(&price, 8).is_err());
+}
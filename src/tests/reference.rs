@@ -0,0 +1,232 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+
+use crate::{
+    error::MonitoringError,
+    reference::{
+        binance::binance_symbol, consensus::consensus, prefetched_price, CachedProvider,
+        ConsensusProvider, FallbackProvider, PrefetchedPrices, ReferencePrice,
+        ReferencePriceProvider,
+    },
+};
+
+/// Reference provider returning a fixed price, or failing if none is set.
+struct MockProvider {
+    name: &'static str,
+    price: Option<f64>,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ReferencePriceProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn historical_price(
+        &self,
+        pair_id: &str,
+        _timestamp: u64,
+    ) -> Result<ReferencePrice, MonitoringError> {
+        self.current_price(pair_id).await
+    }
+
+    async fn current_price(&self, _pair_id: &str) -> Result<ReferencePrice, MonitoringError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        self.price
+            .map(|price| ReferencePrice::new(price, self.name))
+            .ok_or(MonitoringError::Api(format!("{} is down", self.name)))
+    }
+}
+
+fn mock(name: &'static str, price: Option<f64>) -> Box<dyn ReferencePriceProvider> {
+    Box::new(MockProvider {
+        name,
+        price,
+        calls: Arc::default(),
+    })
+}
+
+#[tokio::test]
+async fn fallback_uses_first_available_reference() {
+    let provider =
+        FallbackProvider::new(vec![mock("defillama", None), mock("coingecko", Some(42.0))]);
+
+    let reference = provider.historical_price("BTC/USD", 0).await.unwrap();
+
+    assert_eq!(reference, ReferencePrice::new(42.0, "coingecko"));
+}
+
+#[tokio::test]
+async fn fallback_fails_when_all_references_fail() {
+    let provider = FallbackProvider::new(vec![mock("defillama", None), mock("coingecko", None)]);
+
+    assert!(provider.current_price("BTC/USD").await.is_err());
+}
+
+#[test]
+fn consensus_ignores_outlier_reference() {
+    let consensus = consensus(&[100.0, 101.0, 150.0], 0.02).unwrap();
+
+    assert_eq!(consensus.agreeing, 2);
+    assert_eq!(consensus.price, 100.5);
+    assert_eq!(consensus.spread, 50.0 / 101.0);
+}
+
+#[tokio::test]
+async fn consensus_requires_enough_agreeing_references() {
+    let provider = ConsensusProvider::new(
+        vec![
+            mock("defillama", Some(100.0)),
+            mock("coingecko", Some(110.0)),
+        ],
+        2,
+        0.01,
+    );
+
+    match provider.current_price("BTC/USD").await {
+        Err(MonitoringError::NoConsensus { agreeing, .. }) => assert_eq!(agreeing, 0),
+        other => panic!("Expected no consensus, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn consensus_uses_median_of_references() {
+    let provider = ConsensusProvider::new(
+        vec![
+            mock("defillama", Some(100.0)),
+            mock("coingecko", Some(100.5)),
+            mock("pragma", None),
+        ],
+        2,
+        0.01,
+    );
+
+    let reference = provider.current_price("BTC/USD").await.unwrap();
+
+    assert_eq!(reference.price, 100.25);
+    assert_eq!(reference.reference, "consensus");
+    assert_eq!(reference.spread, Some(0.5 / 100.25));
+}
+
+#[tokio::test]
+async fn cache_coalesces_requests_of_the_same_bucket() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let provider = CachedProvider::new(
+        MockProvider {
+            name: "defillama",
+            price: Some(42.0),
+            calls: calls.clone(),
+        },
+        Duration::from_secs(60),
+        Duration::from_secs(300),
+    );
+
+    let prices = futures::future::join_all(
+        (0..5).map(|i| provider.historical_price("BTC/USD", 1_702_677_600 + i)),
+    )
+    .await;
+    assert!(prices
+        .iter()
+        .all(|price| price.as_ref().unwrap().price == 42.0));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Another bucket requires a new request
+    provider
+        .historical_price("BTC/USD", 1_702_677_600 + 60)
+        .await
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn cache_coalesces_concurrent_batches() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let provider = CachedProvider::new(
+        MockProvider {
+            name: "defillama",
+            price: Some(42.0),
+            calls: calls.clone(),
+        },
+        Duration::from_secs(60),
+        Duration::from_secs(300),
+    );
+
+    // e.g the spot ticks of two networks and a single lookup racing them
+    let timestamp = 1_702_677_642;
+    let first = vec!["BTC/USD".to_string(), "ETH/USD".to_string()];
+    let second = vec!["ETH/USD".to_string(), "STRK/USD".to_string()];
+    let (first, second, single) = tokio::join!(
+        provider.historical_prices(&first, timestamp),
+        provider.historical_prices(&second, timestamp),
+        provider.historical_price("BTC/USD", timestamp),
+    );
+
+    assert!(first
+        .values()
+        .chain(second.values())
+        .all(|price| price.as_ref().unwrap().price == 42.0));
+    assert_eq!(single.unwrap().price, 42.0);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn prefetched_batch_only_serves_entries_of_its_bucket() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let provider = CachedProvider::new(
+        MockProvider {
+            name: "defillama",
+            price: Some(42.0),
+            calls: calls.clone(),
+        },
+        Duration::from_secs(60),
+        Duration::from_secs(300),
+    );
+
+    // Prefetched at the start of the tick
+    let tick = 1_702_677_642;
+    let pairs = vec!["BTC/USD".to_string(), "ETH/USD".to_string()];
+    let prefetched = PrefetchedPrices {
+        timestamp: tick,
+        bucket: 60,
+        prices: provider.historical_prices(&pairs, tick).await,
+    };
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Entries published earlier in the bucket of the tick
+    for pair in ["BTC/USD", "ETH/USD"] {
+        let reference = prefetched_price(&provider, &prefetched, pair, tick - 30)
+            .await
+            .unwrap();
+        assert_eq!(reference.price, 42.0);
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Older entries are compared with the reference price at their own timestamp
+    assert!(prefetched.get("BTC/USD", tick - 600).is_none());
+    prefetched_price(&provider, &prefetched, "BTC/USD", tick - 600)
+        .await
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    // As are the pairs missing from the batch
+    prefetched_price(&provider, &prefetched, "STRK/USD", tick - 30)
+        .await
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn binance_symbol_maps_usd_to_usdt_contract() {
+    assert_eq!(binance_symbol("BTC/USD").unwrap(), "BTCUSDT");
+    assert_eq!(binance_symbol("ETH/USDT").unwrap(), "ETHUSDT");
+    assert!(binance_symbol("BTCUSD").is_err());
+}
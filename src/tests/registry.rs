@@ -0,0 +1,48 @@
+use crate::config::DataType;
+use crate::registry::{RegistryChange, RegistryState};
+
+fn state(publishers: &[&str], sources: &[(&str, &[&str])]) -> RegistryState {
+    RegistryState {
+        publishers: publishers.iter().map(|p| p.to_string()).collect(),
+        sources: sources
+            .iter()
+            .map(|(pair, sources)| {
+                (
+                    (DataType::Spot, pair.to_string()),
+                    sources.iter().map(|s| s.to_string()).collect(),
+                )
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn diffs_publishers_and_sources() {
+    let previous = state(
+        &["AVNU", "SKYNET"],
+        &[("BTC/USD", &["BINANCE", "OKX"]), ("ETH/USD", &["OKX"])],
+    );
+    let current = state(
+        &["AVNU", "FOURLEAF"],
+        &[("BTC/USD", &["BINANCE", "BYBIT"]), ("SOL/USD", &["OKX"])],
+    );
+
+    assert_eq!(
+        current.changes_since(&previous),
+        vec![
+            RegistryChange::PublisherAdded("FOURLEAF".to_string()),
+            RegistryChange::PublisherRemoved("SKYNET".to_string()),
+            RegistryChange::SourceAdded {
+                data_type: "spot".to_string(),
+                pair: "BTC/USD".to_string(),
+                source: "BYBIT".to_string(),
+            },
+            RegistryChange::SourceRemoved {
+                data_type: "spot".to_string(),
+                pair: "BTC/USD".to_string(),
+                source: "OKX".to_string(),
+            },
+        ]
+    );
+    assert!(current.changes_since(&current).is_empty());
+}
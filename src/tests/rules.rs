@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use starknet::core::types::Felt;
+
+use crate::config::{ConfigFile, ConfigInput, NetworkName, SyncingPolicy};
+use crate::rules::generate_rules;
+
+const CONFIG_FILE: &str = r#"
+[networks.testnet.spot_pairs."BTC/USD"]
+thresholds = { price_deviation = 0.025, num_sources = 3 }
+checks = ["price_deviation"]
+sources = { BINANCE = { price_deviation = 0.1 } }
+"#;
+
+#[test]
+fn generates_rules_with_pair_and_source_thresholds() {
+    let file = ConfigFile::parse(CONFIG_FILE).unwrap();
+    let config_input = ConfigInput {
+        network: NetworkName::new(NetworkName::TESTNET),
+        rpc_url: String::new(),
+        chain_id: None,
+        oracle_address: Felt::ZERO,
+        table_prefix: String::new(),
+        api_url: None,
+        sequencer_url: None,
+        vrf_table: None,
+        syncing_policy: SyncingPolicy::default(),
+        spot_pairs: vec!["BTC/USD".to_string()],
+        future_pairs: vec![],
+        reference_ids: HashMap::new(),
+        spot_settings: file.network("testnet").spot_pairs,
+        future_settings: HashMap::new(),
+        ignore_sources: vec![],
+        ignore_publishers: vec![],
+    };
+
+    let rules = generate_rules(&[config_input]);
+
+    let selector = r#"network="Testnet", pair="BTC/USD", type="spot""#;
+    assert!(rules.contains(&format!(
+        r#"expr: 'abs(price_deviation{{{selector}, source!~"BINANCE"}}) > 0.025'"#
+    )));
+    assert!(rules.contains(&format!(
+        r#"expr: 'abs(price_deviation{{{selector}, source="BINANCE"}}) > 0.1'"#
+    )));
+    assert!(rules.contains(&format!("expr: 'num_sources{{{selector}}} < 3'")));
+    assert!(rules.contains(&format!(
+        "expr: 'time_since_last_update_pair_id{{{selector}}} > 1800'"
+    )));
+    // Disabled checks
+    assert!(!rules.contains("price_deviation_source"));
+    assert!(!rules.contains("on_off_price_deviation"));
+}
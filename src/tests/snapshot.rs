@@ -0,0 +1,34 @@
+use prometheus::{opts, GaugeVec, Registry};
+
+use crate::snapshot::{Snapshot, SnapshotFormat};
+
+#[test]
+fn renders_gathered_metrics() {
+    let registry = Registry::new();
+    let gauge = GaugeVec::new(opts!("pair_price", "Price"), &["network", "pair"]).unwrap();
+    registry.register(Box::new(gauge.clone())).unwrap();
+    gauge.with_label_values(&["Testnet", "ETH/USD"]).set(3000.5);
+    gauge
+        .with_label_values(&["Testnet", "BTC/USD"])
+        .set(60000.0);
+
+    let snapshot = Snapshot::new(
+        &registry.gather(),
+        vec!["[Testnet][API] timeout".to_string()],
+    );
+
+    assert_eq!(
+        snapshot.render(SnapshotFormat::Table),
+        "METRIC      LABELS                        VALUE\n\
+         pair_price  network=Testnet pair=BTC/USD  60000\n\
+         pair_price  network=Testnet pair=ETH/USD  3000.5\n\
+         \n\
+         Errors (1):\n  \
+         [Testnet][API] timeout"
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&snapshot.render(SnapshotFormat::Json)).unwrap();
+    assert_eq!(json["samples"][0]["labels"]["pair"], "BTC/USD");
+    assert_eq!(json["samples"][1]["value"], 3000.5);
+}
@@ -0,0 +1,33 @@
+use prometheus::{opts, GaugeVec};
+
+use crate::processing::stale_metrics::retain_series;
+
+#[test]
+fn removes_series_of_the_network_only() {
+    let gauge = GaugeVec::new(opts!("pair_price", "Price"), &["network", "pair"]).unwrap();
+    gauge.with_label_values(&["Testnet", "BTC/USD"]).set(1.0);
+    gauge.with_label_values(&["Testnet", "ETH/USD"]).set(1.0);
+    gauge.with_label_values(&["Mainnet", "ETH/USD"]).set(1.0);
+
+    retain_series(&gauge, "Testnet", |labels| {
+        labels.get("pair") == Some(&"BTC/USD")
+    });
+
+    let families = prometheus::core::Collector::collect(&gauge);
+    let mut series = families[0]
+        .get_metric()
+        .iter()
+        .map(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .map(|label| label.get_value())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    series.sort();
+    assert_eq!(
+        series,
+        vec![vec!["Mainnet", "ETH/USD"], vec!["Testnet", "BTC/USD"]]
+    );
+}
@@ -0,0 +1,70 @@
+use std::ops::Bound;
+
+use bigdecimal::BigDecimal;
+use chrono::{Duration, NaiveDateTime};
+
+use crate::models::VrfRequest;
+use crate::monitoring::vrf::{VrfStats, VrfStatus};
+
+fn request(status: u8, created_at: NaiveDateTime, updated_at: NaiveDateTime) -> VrfRequest {
+    VrfRequest {
+        network: "sepolia".to_string(),
+        request_id: BigDecimal::from(0),
+        seed: BigDecimal::from(0),
+        created_at,
+        created_at_tx: "0x1".to_string(),
+        callback_address: "0x2".to_string(),
+        callback_fee_limit: BigDecimal::from(0),
+        num_words: BigDecimal::from(1),
+        requestor_address: "0x3".to_string(),
+        updated_at,
+        updated_at_tx: "0x4".to_string(),
+        status: BigDecimal::from(status),
+        minimum_block_number: BigDecimal::from(100),
+        _cursor: (Bound::Included(0), Bound::Unbounded),
+        data_id: "0x5".to_string(),
+    }
+}
+
+#[test]
+fn computes_vrf_stats() {
+    let now = NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap();
+    let ago = |seconds| now - Duration::seconds(seconds);
+
+    let requests = vec![
+        request(1, ago(30), ago(30)),
+        request(1, ago(7200), ago(7200)),
+        request(2, ago(100), ago(90)),
+        request(2, ago(100), ago(70)),
+        request(2, ago(100), ago(40)),
+        // Fulfilled before the window
+        request(2, ago(5000), ago(4000)),
+        request(3, ago(100), ago(50)),
+    ];
+    let stats = VrfStats::new(&requests, now, 101, 3600);
+
+    assert_eq!(
+        stats.pending_by_age,
+        vec![
+            ("<1m", 1),
+            ("1m-5m", 0),
+            ("5m-15m", 0),
+            ("15m-1h", 0),
+            (">1h", 1)
+        ]
+    );
+    assert_eq!(stats.oldest_pending, 7200);
+    assert_eq!(stats.past_minimum_block, 2);
+    assert_eq!(stats.latencies, vec![10, 30, 60]);
+    assert_eq!(stats.latency_quantile(0.5), Some(30));
+    assert_eq!(stats.latency_quantile(1.0), Some(60));
+
+    assert_eq!(
+        VrfStatus::from_code(&BigDecimal::from(4)).to_string(),
+        "out_of_gas"
+    );
+    assert_eq!(
+        VrfStatus::from_code(&BigDecimal::from(9)),
+        VrfStatus::Unknown
+    );
+}
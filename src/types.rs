@@ -14,7 +14,7 @@ pub trait Entry {
     fn block_number(&self) -> i64;
     fn price(&self) -> BigDecimal;
     fn expiration_timestamp(&self) -> Option<NaiveDateTime>;
-    fn data_type(&self) -> DataType;
+    fn data_type() -> DataType;
 }
 
 impl Entry for SpotEntry {
@@ -42,7 +42,7 @@ impl Entry for SpotEntry {
         None
     }
 
-    fn data_type(&self) -> DataType {
+    fn data_type() -> DataType {
         DataType::Spot
     }
 }
@@ -72,7 +72,7 @@ impl Entry for FutureEntry {
         self.expiration_timestamp
     }
 
-    fn data_type(&self) -> DataType {
+    fn data_type() -> DataType {
         DataType::Future
     }
 }
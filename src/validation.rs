@@ -0,0 +1,109 @@
+use std::fmt;
+
+/// Severity of a configuration issue.
+/// Warnings only disable what they are about (a pair, a publisher...) while
+/// fatal issues prevent the network from being monitored at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Fatal,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("WARN"),
+            Severity::Fatal => f.write_str("FATAL"),
+        }
+    }
+}
+
+/// A problem found while validating the configuration of a network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Network the issue is about, `None` for the global settings.
+    pub network: Option<String>,
+    /// What the issue is about, e.g a pair, a publisher or a setting.
+    pub subject: String,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}]", self.severity)?;
+        if let Some(network) = &self.network {
+            write!(f, "[{network}]")?;
+        }
+        write!(f, " {}: {}", self.subject, self.message)
+    }
+}
+
+/// Issues found while validating the configuration, see `Config::new`.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    issues: Vec<Issue>,
+}
+
+#[allow(unused)]
+impl ValidationReport {
+    pub fn warning(&mut self, network: Option<&str>, subject: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, network, subject, message.into());
+    }
+
+    pub fn fatal(&mut self, network: Option<&str>, subject: &str, message: impl Into<String>) {
+        self.push(Severity::Fatal, network, subject, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, network: Option<&str>, subject: &str, message: String) {
+        self.issues.push(Issue {
+            severity,
+            network: network.map(str::to_string),
+            subject: subject.to_string(),
+            message,
+        });
+    }
+
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.issues.extend(other.issues);
+    }
+
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == severity)
+            .count()
+    }
+
+    /// Whether the configuration cannot be used.
+    pub fn is_fatal(&self) -> bool {
+        self.count(Severity::Fatal) > 0
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Configuration report: {} fatal error(s), {} warning(s)",
+            self.count(Severity::Fatal),
+            self.count(Severity::Warning)
+        )?;
+
+        // Fatal issues first, in the order they were found
+        let mut issues = self.issues.iter().collect::<Vec<_>>();
+        issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+        for issue in issues {
+            write!(f, "\n  {issue}")?;
+        }
+        Ok(())
+    }
+}